@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use crate::error::Error;
+
+/** The set of letters that words and tiles can be made of. Every letter has an index (its position in the alphabet),
+ * which dictionaries use to lay out the possible next letters of a word. English needs 26 letters, but other languages
+ * add accented letters like é, ñ, ü and ß.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    name: String,
+    letters: Vec<char>,
+    // Index of every ASCII letter, for the common case
+    ascii: [Option<u8>; 128],
+    // Index of every other letter
+    others: HashMap<char, usize>,
+}
+impl Alphabet {
+    /** Define an alphabet from its letters, in order. Repeated letters are ignored. An alphabet can hold at most 256
+     * letters; any more is an AlphabetTooLarge error.
+     */
+    pub fn new(name: &str, letters: &str) -> Result<Alphabet, Error> {
+        let mut alphabet = Alphabet {
+            name: String::from(name),
+            letters: Vec::new(),
+            ascii: [None; 128],
+            others: HashMap::new(),
+        };
+        for letter in letters.chars() {
+            if alphabet.contains(letter) {
+                continue;
+            }
+            let index = alphabet.letters.len();
+            if letter.is_ascii() {
+                alphabet.ascii[letter as usize] = Some(index as u8);
+            } else {
+                alphabet.others.insert(letter, index);
+            }
+            alphabet.letters.push(letter);
+        }
+        // Indexes past 255 don't fit in the ASCII table, but then the alphabet is rejected anyway
+        if alphabet.letters.len() > 256 {
+            return Err(Error::AlphabetTooLarge {
+                letters: alphabet.letters.len(),
+            });
+        }
+        Ok(alphabet)
+    }
+
+    pub fn english() -> Alphabet {
+        Alphabet::built_in("english", Alphabet::LATIN)
+    }
+
+    pub fn french() -> Alphabet {
+        Alphabet::built_in("french", &format!("{}àâæçéèêëîïôœùûüÿ", Alphabet::LATIN))
+    }
+
+    pub fn spanish() -> Alphabet {
+        Alphabet::built_in("spanish", &format!("{}áéíñóúü", Alphabet::LATIN))
+    }
+
+    pub fn german() -> Alphabet {
+        Alphabet::built_in("german", &format!("{}äöüß", Alphabet::LATIN))
+    }
+
+    // The built-in alphabets are all well under the limit
+    fn built_in(name: &str, letters: &str) -> Alphabet {
+        Alphabet::new(name, letters).expect("built-in alphabets have at most 256 letters")
+    }
+
+    /** Look up one of the built-in alphabets by name.
+     */
+    pub fn by_name(name: &str) -> Option<Alphabet> {
+        match name {
+            "english" => Some(Alphabet::english()),
+            "french" => Some(Alphabet::french()),
+            "spanish" => Some(Alphabet::spanish()),
+            "german" => Some(Alphabet::german()),
+            _ => None,
+        }
+    }
+
+    const LATIN: &'static str = "abcdefghijklmnopqrstuvwxyz";
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /** The number of letters in the alphabet.
+     */
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    pub fn letters(&self) -> &[char] {
+        &self.letters
+    }
+
+    /** The position of a letter in the alphabet, or None if it isn't part of the alphabet.
+     */
+    pub fn index(&self, letter: char) -> Option<usize> {
+        if letter.is_ascii() {
+            self.ascii[letter as usize].map(usize::from)
+        } else {
+            self.others.get(&letter).copied()
+        }
+    }
+
+    /** The letter at a position in the alphabet.
+     */
+    pub fn letter(&self, index: usize) -> char {
+        self.letters[index]
+    }
+
+    pub fn contains(&self, letter: char) -> bool {
+        self.index(letter).is_some()
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Alphabet {
+        Alphabet::english()
+    }
+}
+
+/** The unaccented letter that an accented letter is based on, so that (for example) é can be valued like e. Letters
+ * without an accent are returned as they are.
+ */
+pub fn base_letter(letter: char) -> char {
+    match letter {
+        'à' | 'á' | 'â' | 'ä' | 'æ' => 'a',
+        'ç' => 'c',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'í' | 'î' | 'ï' => 'i',
+        'ñ' => 'n',
+        'ó' | 'ô' | 'ö' | 'œ' => 'o',
+        'ß' => 's',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        'ÿ' => 'y',
+        _ => letter,
+    }
+}
+
+// TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_english() {
+        let english = Alphabet::english();
+        assert_eq!(english.len(), 26);
+        assert_eq!(english.index('a'), Some(0));
+        assert_eq!(english.index('z'), Some(25));
+        assert_eq!(english.index('A'), None);
+        assert_eq!(english.index('é'), None);
+        assert_eq!(english.letter(2), 'c');
+    }
+
+    #[test]
+    fn test_european() {
+        let german = Alphabet::german();
+        assert_eq!(german.len(), 30);
+        assert_eq!(german.index('ß'), Some(29));
+        assert!(!german.contains('ñ'));
+        let spanish = Alphabet::by_name("spanish").unwrap();
+        assert_eq!(spanish.letter(spanish.index('ñ').unwrap()), 'ñ');
+        assert_eq!(Alphabet::french().len(), 42);
+        assert!(Alphabet::by_name("klingon").is_none());
+        // Repeated letters only take up one slot
+        assert_eq!(Alphabet::new("abc", "abcabc").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_too_large() {
+        let letters: String = (0x100..0x200).filter_map(std::char::from_u32).collect();
+        assert_eq!(Alphabet::new("big", &letters).unwrap().len(), 256);
+        let letters = format!("{}a", letters);
+        assert!(matches!(
+            Alphabet::new("bigger", &letters),
+            Err(Error::AlphabetTooLarge { letters: 257 })
+        ));
+    }
+
+    #[test]
+    fn test_base_letter() {
+        assert_eq!(base_letter('é'), 'e');
+        assert_eq!(base_letter('ß'), 's');
+        assert_eq!(base_letter('q'), 'q');
+    }
+}
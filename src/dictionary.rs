@@ -1,38 +1,202 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::TryInto;
+use std::fmt;
+use std::fs;
+use std::path::Path;
 
-/** An entry in the dictionary data structure can either be empty or a letter.
- */
-#[derive(Clone, Debug)]
-enum Entry {
-    Empty,
-    Present(Letter),
-}
+use crate::alphabet::Alphabet;
+use crate::error::Error;
 
-/** This is pretty similar to a linked list-each node contains a link to the next items. The difference is that
- * when implemented, each Vec<Entry> contains 26 elements (one for each possible letter), which makes indexing into
- * it a matter of converting from ASCII value to int, which should be constant-time. A basic Linked List would take
- * O(n) to find a letter.
+/** A node in a GraphDictionary's trie. Nodes are stored in one flat array, and the children of each node sit next to
+ * each other in that array, sorted by their position in the alphabet. So instead of every node owning a table with a slot
+ * for every letter of the alphabet (almost all of them empty), a node only needs to know where its children start and
+ * how many there are. Finding a child is a binary search over at most one entry per letter.
  */
-#[derive(Clone, Default, Debug)]
-struct Letter {
-    c: char,
+#[derive(Clone, Copy, Debug, Default)]
+struct Node {
+    first_child: u32,
+    child_count: u16,
+    // The node's letter, as its index in the alphabet
+    letter: u16,
     is_word: bool,
-    possible_next_letters: Box<Vec<Entry>>,
 }
 
 /**
- * Every dictionary should have an implementation of a word finding function on it
+ * Every dictionary should have an implementation of a word finding function on it. Dictionaries are searched with a
+ * Cursor, which marks a position in the dictionary (the letters that have been stepped through so far). This lets the
+ * solver move through the dictionary one letter at a time as it moves through the grid, instead of looking up every
+ * candidate string from scratch.
  */
 pub trait Dictionary {
+    type Cursor<'a>: Clone
+    where
+        Self: 'a;
+
+    /**
+     * A cursor at the start of the dictionary, before any letters have been stepped through.
+     */
+    fn root(&self) -> Self::Cursor<'_>;
+
+    /**
+     * Move the cursor forward by one letter. Returns None if no word continues with that letter.
+     */
+    fn step<'a>(&'a self, cursor: &Self::Cursor<'a>, letter: char) -> Option<Self::Cursor<'a>>;
+
+    /**
+     * Move the cursor forward by every letter in `letters`, in order. Returns None if no word continues with them.
+     */
+    fn step_letters<'a>(
+        &'a self,
+        cursor: &Self::Cursor<'a>,
+        letters: &str,
+    ) -> Option<Self::Cursor<'a>> {
+        let mut cursor = cursor.clone();
+        for letter in letters.chars() {
+            cursor = self.step(&cursor, letter)?;
+        }
+        Some(cursor)
+    }
+
+    /**
+     * Whether the letters stepped through so far make up a word.
+     */
+    fn is_word_at<'a>(&'a self, cursor: &Self::Cursor<'a>) -> bool;
+
+    /**
+     * Whether any longer word starts with the letters stepped through so far.
+     */
+    fn has_children<'a>(&'a self, cursor: &Self::Cursor<'a>) -> bool;
+
     /**
      * Should return a 2-tuple of the form (is a word, is a terminal word)
      */
-    fn is_word(dict: &Self, letters: &str) -> (bool, bool);
+    fn is_word(dict: &Self, letters: &str) -> (bool, bool) {
+        match dict.step_letters(&dict.root(), letters) {
+            None => (false, true),
+            Some(cursor) => (dict.is_word_at(&cursor), !dict.has_children(&cursor)),
+        }
+    }
+}
+
+/** A borrowed dictionary works just like the dictionary itself, so a Solver can borrow one dictionary to solve many
+ * grids instead of needing its own copy.
+ */
+impl<D: Dictionary> Dictionary for &D {
+    type Cursor<'a>
+        = D::Cursor<'a>
+    where
+        Self: 'a;
+
+    fn root(&self) -> Self::Cursor<'_> {
+        (**self).root()
+    }
+
+    fn step<'a>(&'a self, cursor: &Self::Cursor<'a>, letter: char) -> Option<Self::Cursor<'a>> {
+        (**self).step(cursor, letter)
+    }
+
+    fn step_letters<'a>(
+        &'a self,
+        cursor: &Self::Cursor<'a>,
+        letters: &str,
+    ) -> Option<Self::Cursor<'a>> {
+        (**self).step_letters(cursor, letters)
+    }
+
+    fn is_word_at<'a>(&'a self, cursor: &Self::Cursor<'a>) -> bool {
+        (**self).is_word_at(cursor)
+    }
+
+    fn has_children<'a>(&'a self, cursor: &Self::Cursor<'a>) -> bool {
+        (**self).has_children(cursor)
+    }
 }
 
+/* A flat dictionary built out of hash tables. Every word is stored alongside a payload (its position in the source
+ * list by default), and every proper prefix of every word is stored in a separate set so that the dictionary can tell
+ * when a sequence of letters can't lead to any longer word.
+ *
+ * This is much quicker to build than a GraphDictionary, but each lookup hashes the whole candidate string.
+ */
 #[derive(Debug)]
 pub struct HashMapDictionary {
-    words: Box<HashMap<String, u32>>,
+    words: HashMap<String, u32>,
+    prefixes: HashSet<String>,
+}
+impl Dictionary for HashMapDictionary {
+    // The letters stepped through so far
+    type Cursor<'a> = String;
+
+    fn root(&self) -> String {
+        String::new()
+    }
+
+    fn step(&self, cursor: &String, letter: char) -> Option<String> {
+        let mut next = cursor.clone();
+        next.push(letter);
+        if self.words.contains_key(&next) || self.prefixes.contains(&next) {
+            Some(next)
+        } else {
+            None
+        }
+    }
+
+    fn is_word_at(&self, cursor: &String) -> bool {
+        self.words.contains_key(cursor)
+    }
+
+    fn has_children(&self, cursor: &String) -> bool {
+        if cursor.is_empty() {
+            !self.words.is_empty()
+        } else {
+            self.prefixes.contains(cursor)
+        }
+    }
+}
+
+impl HashMapDictionary {
+    /** Build a dictionary from a list of words. Each word's payload is its position in the list.
+     */
+    pub fn new(source_dictionary: &[String]) -> HashMapDictionary {
+        HashMapDictionary::with_payloads(
+            source_dictionary
+                .iter()
+                .enumerate()
+                .map(|(i, word)| (word.clone(), i as u32)),
+        )
+    }
+
+    /** Build a dictionary from (word, payload) pairs. If a word appears more than once, the last payload wins. Empty
+     * words are skipped.
+     */
+    pub fn with_payloads<I: IntoIterator<Item = (String, u32)>>(words: I) -> HashMapDictionary {
+        let mut dict = HashMapDictionary {
+            words: HashMap::new(),
+            prefixes: HashSet::new(),
+        };
+        for (word, payload) in words.into_iter().filter(|(word, _)| !word.is_empty()) {
+            // Every proper prefix of the word can be extended into the word, so none of them are terminal
+            for (i, _) in word.char_indices().skip(1) {
+                dict.prefixes.insert(String::from(&word[..i]));
+            }
+            dict.words.insert(word, payload);
+        }
+        dict
+    }
+
+    /** Returns the payload stored with a word, or None if it isn't in the dictionary.
+     */
+    pub fn payload(&self, word: &str) -> Option<u32> {
+        self.words.get(word).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
 }
 
 /* This dictionary translates words into maps of letters in a directed graph of the form:
@@ -41,175 +205,1009 @@ pub struct HashMapDictionary {
  *        |...
  * ;
  * 'b' -> | 'ba' -> ... ... ... 'barter' -> ... ... ... 'bartered' (terminal word)
- *        | bb -> Empty (no words start with 'bb')
+ *        | 'be' -> ... (no words start with 'bb', so it's left out)
  *        |...
  * ...
  * One advantage of this over a traditional LUT dictionary is that this allows you to determine whether a path is worth
  * traversing any further. If a letter has no descendents, then no words can be created by continuing. This allows for
  * the case in which cells in the grid may be repeatedly added to form words.
  *
- * TODO: If an alternative to find_word is implemented that keeps track of a position in the dictionary, querying for
- * words could be done linearly at each step of iteration through the grid. This would be faster than traversing the
- * dictionary for a word at each step.
+ * The graph is stored as a flat array of nodes (see Node), with the root at index 0.
  */
 #[derive(Debug)]
 pub struct GraphDictionary {
-    words: Box<Vec<Entry>>,
+    alphabet: Alphabet,
+    nodes: Vec<Node>,
 }
+
+/** A position in a GraphDictionary: the node for the letters stepped through so far.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct GraphCursor {
+    node: u32,
+}
+
 impl Dictionary for GraphDictionary {
-    /**
-    Given a candidate word, return two things:
-    * Whether the candidate is a word or not
-    * Whether the sequence of letters is terminal in the dictionary (and thus a search need not continue down this path)
-    */
-    fn is_word(dict: &Self, letters: &str) -> (bool, bool) {
-        match dict.find_word(letters) {
-            None => (false, true),
-            Some(l) => {
-                // If any possible next letter is present, it isn't terminal
-                for possible_l in l.possible_next_letters.into_iter() {
-                    match possible_l {
-                        Entry::Empty => {}
-                        Entry::Present(_) => return (true, false),
-                    }
-                }
-                return (true, true);
-            }
-        }
+    type Cursor<'a> = GraphCursor;
+
+    fn root(&self) -> GraphCursor {
+        GraphCursor { node: 0 }
+    }
+
+    fn step(&self, cursor: &GraphCursor, letter: char) -> Option<GraphCursor> {
+        let index = self.alphabet.index(letter)? as u16;
+        let children = self.children(cursor.node);
+        let i = children.binary_search_by_key(&index, |n| n.letter).ok()?;
+        Some(GraphCursor {
+            node: self.nodes[cursor.node as usize].first_child + i as u32,
+        })
+    }
+
+    fn is_word_at(&self, cursor: &GraphCursor) -> bool {
+        self.nodes[cursor.node as usize].is_word
+    }
+
+    fn has_children(&self, cursor: &GraphCursor) -> bool {
+        self.nodes[cursor.node as usize].child_count > 0
     }
 }
 
 impl GraphDictionary {
-    const ASCII_a_VALUE: usize = 'a' as usize;
-    // const ASCII_Z_VALUE: usize = 122;
+    /** Build an English dictionary from a list of words. Every word must be made up of the letters a-z; use
+     * `normalize` to clean up a raw word list first.
+     */
+    pub fn new(source_dictionary: &[String]) -> Result<GraphDictionary, Error> {
+        GraphDictionary::with_alphabet(source_dictionary, Alphabet::english())
+    }
 
-    pub fn new(source_dictionary: &Vec<String>) -> GraphDictionary {
-        // TODO: what if capitalized?
-        // TODO: should remove duplicates
-        GraphDictionary {
-            words: GraphDictionary::translate_dictionary_to_word_map(source_dictionary),
-        }
+    /** Build a dictionary from a list of words, every one of which must be made up of letters from the alphabet.
+     */
+    pub fn with_alphabet(
+        source_dictionary: &[String],
+        alphabet: Alphabet,
+    ) -> Result<GraphDictionary, Error> {
+        Ok(GraphDictionary {
+            nodes: GraphDictionary::translate_dictionary_to_word_map(source_dictionary, &alphabet)?,
+            alphabet,
+        })
     }
 
-    /** This is a slower way of traversing the dictionary. Instead of proceeding step-by-step as you progress through the
-    grid, this passes a candidate word to the Dictionary, which returns a boolean if it finds it.
-    */
-    fn find_word(&self, letters: &str) -> Option<Letter> {
-        let mut current_letter: &Letter = &Letter::default();
-        if letters == "" {
-            return None;
-        }
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
 
-        for (i, letter) in letters.chars().enumerate() {
-            let index = letter as usize - GraphDictionary::ASCII_a_VALUE;
-            // TODO: Could get rid of this "is i == 0" nonsense by making the dictionary start with an (always Present) Entry.
-            if i == 0 {
-                match &self.words[index] {
-                    Entry::Empty => return None,
-                    Entry::Present(letter) => current_letter = &letter,
-                }
-            } else {
-                match &current_letter.possible_next_letters[index] {
-                    Entry::Empty => return None,
-                    Entry::Present(letter) => current_letter = &letter,
-                }
-            }
-        }
-        return Some(current_letter.clone()); // TODO: does this clone the entire dictionary?
+    /** The number of nodes in the graph, including the root.
+     */
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /** Roughly how many bytes the dictionary takes up in memory, including the nodes it has allocated.
+     */
+    pub fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>() + self.nodes.capacity() * std::mem::size_of::<Node>()
+    }
+
+    /** Build a dictionary from a file containing one word per line, normalized with the default options.
+     */
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<GraphDictionary, Error> {
+        GraphDictionary::load(path, &NormalizeOptions::default()).map(|(dict, _)| dict)
+    }
+
+    /** Build a dictionary from a file containing one word per line, normalized with the given options. Also returns
+     * a report of which lines were skipped.
+     */
+    pub fn load<P: AsRef<Path>>(
+        path: P,
+        options: &NormalizeOptions,
+    ) -> Result<(GraphDictionary, LoadReport), Error> {
+        load_with(path, options, GraphDictionary::with_alphabet)
     }
 
     /** Print the dictionary out in the linked format.
      */
     pub fn to_string(dict: &Self) -> String {
-        fn to_string_recursive(words: &Vec<Entry>, spaces: usize) -> String {
+        fn to_string_recursive(dict: &GraphDictionary, node: u32, spaces: usize) -> String {
             let mut string = String::new();
-            for (i, entry) in words.into_iter().enumerate() {
-                match entry {
-                    Entry::Empty => {}
-                    Entry::Present(letter) => {
-                        let is_word_string = if letter.is_word {
-                            "is word"
-                        } else {
-                            "not word"
-                        };
-                        let str_addition = format!(
-                            "{}: {} -> ",
-                            (i as u8 + GraphDictionary::ASCII_a_VALUE as u8) as char,
-                            is_word_string
-                        );
-                        let spaces_str = "- ".repeat(spaces);
-                        string.push_str(format!("{}{}\n", spaces_str, str_addition).as_str());
-                        string.push_str(
-                            to_string_recursive(&letter.possible_next_letters, spaces + 1).as_str(),
-                        );
-                    }
-                }
+            let first_child = dict.nodes[node as usize].first_child;
+            for (i, child) in dict.children(node).iter().enumerate() {
+                let is_word_string = if child.is_word { "is word" } else { "not word" };
+                let str_addition = format!(
+                    "{}: {} -> ",
+                    dict.alphabet.letter(child.letter as usize),
+                    is_word_string
+                );
+                let spaces_str = "- ".repeat(spaces);
+                string.push_str(format!("{}{}\n", spaces_str, str_addition).as_str());
+                string.push_str(
+                    to_string_recursive(dict, first_child + i as u32, spaces + 1).as_str(),
+                );
             }
             string
         }
-        to_string_recursive(&dict.words, 0)
+        to_string_recursive(dict, 0, 0)
+    }
+
+    // The children of a node, sorted by letter
+    fn children(&self, node: u32) -> &[Node] {
+        let node = &self.nodes[node as usize];
+        let first = node.first_child as usize;
+        &self.nodes[first..first + node.child_count as usize]
     }
 
     /** Takes a list of words and encodes them in the linked dictionary format. This format allows the solver to
      * iteratively search through the dictionary at each step of grid traversal, instead of having to iterate through
      * the entire dictionary at each step (sort of similar to depth-first search, I suppose).
+     *
+     * The words are sorted (by alphabet position), so that the words below any node form one contiguous run of the
+     * list. The nodes are then laid out breadth-first: each node's run is split up by the letter after its prefix, and
+     * those letters become its children, added to the end of the array together.
      */
-    fn translate_dictionary_to_word_map(source_dictionary: &Vec<String>) -> Box<Vec<Entry>> {
-        let mut dict: Box<Vec<Entry>> = Box::new(vec![Entry::Empty; 26]);
-        let mut current_letter: &mut Entry = &mut Entry::Empty;
-
-        for word in source_dictionary {
-            let w = word.clone();
-            for (i, character) in w.into_bytes().into_iter().enumerate() {
-                let cur_is_word: bool = if i == word.len() - 1 { true } else { false };
-                // If the first letter in the word
-                if i == 0 {
-                    match &mut dict[(character as usize) - GraphDictionary::ASCII_a_VALUE] {
-                        Entry::Empty => {
-                            dict[(character as usize) - GraphDictionary::ASCII_a_VALUE] =
-                                Entry::Present(Letter {
-                                    c: character as char,
-                                    is_word: cur_is_word,
-                                    possible_next_letters: Box::new(vec![Entry::Empty; 26]),
-                                })
-                        }
-                        // If the letter is already present, all we need to update is whether the letter is a word or not.
-                        Entry::Present(letter) => {
-                            letter.is_word |= cur_is_word;
-                        }
-                    }
-                    // Pointer to where in the data structure we currently are.
-                    current_letter =
-                        &mut dict[(character as usize) - GraphDictionary::ASCII_a_VALUE];
-                } else {
-                    match current_letter {
-                        Entry::Empty => println!("Failure! Incorrectly set to Empty"),
-                        Entry::Present(cl) => {
-                            match &mut cl.possible_next_letters
-                                [(character as usize) - GraphDictionary::ASCII_a_VALUE]
-                            {
-                                // If the letter isn't present, fill it in with a new Letter entry.
-                                Entry::Empty => {
-                                    cl.possible_next_letters
-                                        [(character as usize) - GraphDictionary::ASCII_a_VALUE] =
-                                        Entry::Present(Letter {
-                                            c: character as char,
-                                            is_word: cur_is_word,
-                                            possible_next_letters: Box::new(vec![Entry::Empty; 26]),
-                                        })
-                                }
-                                // If the letter is already present, all we need to update is whether the letter is a word or not.
-                                Entry::Present(letter) => {
-                                    letter.is_word |= cur_is_word;
-                                }
-                            }
-                            current_letter = &mut cl.possible_next_letters
-                                [(character as usize) - GraphDictionary::ASCII_a_VALUE];
-                        }
-                    }
+    fn translate_dictionary_to_word_map(
+        source_dictionary: &[String],
+        alphabet: &Alphabet,
+    ) -> Result<Vec<Node>, Error> {
+        let words = encode_words(source_dictionary, alphabet)?;
+
+        let mut nodes: Vec<Node> = vec![Node::default()];
+        // (node, the range of words below it, the length of its prefix)
+        let mut queue: VecDeque<(usize, usize, usize, usize)> = VecDeque::new();
+        queue.push_back((0, 0, words.len(), 0));
+        while let Some((node, mut start, end, depth)) = queue.pop_front() {
+            // A word that is exactly the prefix sorts before every longer word that starts with it
+            if start < end && words[start].len() == depth {
+                nodes[node].is_word = true;
+                start += 1;
+            }
+            nodes[node].first_child = nodes.len() as u32;
+            while start < end {
+                let letter = words[start][depth];
+                let run = words[start..end].partition_point(|w| w[depth] == letter);
+                queue.push_back((nodes.len(), start, start + run, depth + 1));
+                nodes.push(Node {
+                    letter,
+                    ..Node::default()
+                });
+                nodes[node].child_count += 1;
+                start += run;
+            }
+        }
+        nodes.shrink_to_fit();
+        Ok(nodes)
+    }
+}
+
+/** Turn words into their letters' positions in an alphabet, sorted and without duplicates or empty words, ready to be
+ * built into a graph.
+ */
+fn encode_words(source_dictionary: &[String], alphabet: &Alphabet) -> Result<Vec<Vec<u16>>, Error> {
+    let mut words = source_dictionary
+        .iter()
+        .map(|word| {
+            word.chars()
+                .map(|character| {
+                    alphabet
+                        .index(character)
+                        .map(|index| index as u16)
+                        .ok_or_else(|| Error::InvalidCharacter {
+                            word: word.clone(),
+                            character,
+                        })
+                })
+                .collect::<Result<Vec<u16>, Error>>()
+        })
+        .collect::<Result<Vec<Vec<u16>>, Error>>()?;
+    words.retain(|w| !w.is_empty());
+    words.sort_unstable();
+    words.dedup();
+    Ok(words)
+}
+
+/* A directed acyclic word graph: like a GraphDictionary, except that words share their endings as well as their
+ * beginnings. Any two points in the graph with exactly the same set of ways to finish a word are merged into one node,
+ * so "tap", "taps", "top" and "tops" only need five nodes:
+ *
+ *               a
+ * root -t-> (t) ---> (ta, to) -p-> (tap, top) -s-> (taps, tops)
+ *               o
+ *
+ * Because a node can be reached by more than one prefix, nodes don't know their own letter; the letter belongs to the
+ * edge leading into the node. Lookups work exactly like a GraphDictionary, but large word lists take up a fraction of
+ * the space.
+ */
+#[derive(Debug)]
+pub struct DawgDictionary {
+    alphabet: Alphabet,
+    nodes: Vec<DawgNode>,
+    edges: Vec<DawgEdge>,
+}
+
+/** A node in a DawgDictionary. Each node's edges are stored next to each other, sorted by letter.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+struct DawgNode {
+    first_edge: u32,
+    edge_count: u16,
+    is_word: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct DawgEdge {
+    // The edge's letter, as its index in the alphabet
+    letter: u16,
+    target: u32,
+}
+
+/** A position in a DawgDictionary: the node reached by the letters stepped through so far.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct DawgCursor {
+    node: u32,
+}
+
+impl Dictionary for DawgDictionary {
+    type Cursor<'a> = DawgCursor;
+
+    fn root(&self) -> DawgCursor {
+        DawgCursor { node: 0 }
+    }
+
+    fn step(&self, cursor: &DawgCursor, letter: char) -> Option<DawgCursor> {
+        let index = self.alphabet.index(letter)? as u16;
+        let edges = self.edges(cursor.node);
+        let i = edges.binary_search_by_key(&index, |e| e.letter).ok()?;
+        Some(DawgCursor {
+            node: edges[i].target,
+        })
+    }
+
+    fn is_word_at(&self, cursor: &DawgCursor) -> bool {
+        self.nodes[cursor.node as usize].is_word
+    }
+
+    fn has_children(&self, cursor: &DawgCursor) -> bool {
+        self.nodes[cursor.node as usize].edge_count > 0
+    }
+}
+
+impl DawgDictionary {
+    /** Build an English dictionary from a list of words, which must be cleaned up as for GraphDictionary::new.
+     */
+    pub fn new(source_dictionary: &[String]) -> Result<DawgDictionary, Error> {
+        DawgDictionary::with_alphabet(source_dictionary, Alphabet::english())
+    }
+
+    /** Build a dictionary from a list of words in the alphabet.
+     *
+     * The graph is built in one pass over the sorted words (Daciuk et al.'s incremental construction): each word is
+     * added as a new branch off the longest prefix it shares with the previous word, and once a branch can no longer
+     * change (because the next word doesn't share it) its nodes are merged with any identical nodes already built.
+     */
+    pub fn with_alphabet(
+        source_dictionary: &[String],
+        alphabet: Alphabet,
+    ) -> Result<DawgDictionary, Error> {
+        let words = encode_words(source_dictionary, &alphabet)?;
+
+        let mut builder = DawgBuilder {
+            nodes: vec![BuildNode::default()],
+            register: HashMap::new(),
+            unchecked: Vec::new(),
+        };
+        let mut previous: &[u16] = &[];
+        for word in &words {
+            let common = word
+                .iter()
+                .zip(previous)
+                .take_while(|(a, b)| a == b)
+                .count();
+            builder.minimize(common);
+            let mut node = builder.unchecked.last().map_or(0, |&(_, _, child)| child);
+            for &letter in &word[common..] {
+                let child = builder.nodes.len() as u32;
+                builder.nodes.push(BuildNode::default());
+                builder.nodes[node as usize].edges.push(DawgEdge {
+                    letter,
+                    target: child,
+                });
+                builder.unchecked.push((node, letter, child));
+                node = child;
+            }
+            builder.nodes[node as usize].is_word = true;
+            previous = word;
+        }
+        builder.minimize(0);
+
+        // Flatten the nodes' edge lists into one array
+        let mut nodes = Vec::with_capacity(builder.nodes.len());
+        let mut edges = Vec::new();
+        for node in builder.nodes {
+            nodes.push(DawgNode {
+                first_edge: edges.len() as u32,
+                edge_count: node.edges.len() as u16,
+                is_word: node.is_word,
+            });
+            edges.extend(node.edges);
+        }
+        edges.shrink_to_fit();
+        Ok(DawgDictionary {
+            alphabet,
+            nodes,
+            edges,
+        })
+    }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /** The number of nodes in the graph, including the root.
+     */
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /** Roughly how many bytes the dictionary takes up in memory, including the nodes and edges it has allocated.
+     */
+    pub fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.nodes.capacity() * std::mem::size_of::<DawgNode>()
+            + self.edges.capacity() * std::mem::size_of::<DawgEdge>()
+    }
+
+    /** Build a dictionary from a word list file, as GraphDictionary::from_file does.
+     */
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<DawgDictionary, Error> {
+        DawgDictionary::load(path, &NormalizeOptions::default()).map(|(dict, _)| dict)
+    }
+
+    /** Build a dictionary from a word list file, as GraphDictionary::load does.
+     */
+    pub fn load<P: AsRef<Path>>(
+        path: P,
+        options: &NormalizeOptions,
+    ) -> Result<(DawgDictionary, LoadReport), Error> {
+        load_with(path, options, DawgDictionary::with_alphabet)
+    }
+
+    /** The first bytes of every compiled dictionary file.
+     */
+    pub const MAGIC: &'static [u8; 8] = b"WORDDAWG";
+    /** The version of the compiled format written by `to_bytes`. Files with any other version are rejected.
+     */
+    pub const FORMAT_VERSION: u32 = 1;
+
+    /** Whether some bytes look like a compiled dictionary (as opposed to a word list).
+     */
+    pub fn is_compiled(bytes: &[u8]) -> bool {
+        bytes.starts_with(DawgDictionary::MAGIC)
+    }
+
+    /** Compile the dictionary into bytes that `from_bytes` can load back without rebuilding the graph. The format is,
+     * with every number little-endian:
+     * - the magic bytes "WORDDAWG", then the format version (u32)
+     * - the alphabet's name and its letters, each as a length in bytes (u32) followed by UTF-8
+     * - the number of nodes and the number of edges (u32 each)
+     * - every node: its first edge (u32), its number of edges (u16), whether it's a word (u8) and a padding byte
+     * - every edge: its letter's index in the alphabet (u16) and the node it leads to (u32)
+     * - an FNV-1a hash of everything before it (u64)
+     */
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let letters: String = self.alphabet.letters().iter().collect();
+        let mut bytes = Vec::with_capacity(
+            64 + self.alphabet.name().len()
+                + letters.len()
+                + self.nodes.len() * 8
+                + self.edges.len() * 6,
+        );
+        bytes.extend_from_slice(DawgDictionary::MAGIC);
+        bytes.extend_from_slice(&DawgDictionary::FORMAT_VERSION.to_le_bytes());
+        for text in [self.alphabet.name(), letters.as_str()] {
+            bytes.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(text.as_bytes());
+        }
+        bytes.extend_from_slice(&(self.nodes.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.edges.len() as u32).to_le_bytes());
+        for node in &self.nodes {
+            bytes.extend_from_slice(&node.first_edge.to_le_bytes());
+            bytes.extend_from_slice(&node.edge_count.to_le_bytes());
+            bytes.extend_from_slice(&[node.is_word as u8, 0]);
+        }
+        for edge in &self.edges {
+            bytes.extend_from_slice(&edge.letter.to_le_bytes());
+            bytes.extend_from_slice(&edge.target.to_le_bytes());
+        }
+        let checksum = fnv1a(&bytes);
+        bytes.extend_from_slice(&checksum.to_le_bytes());
+        bytes
+    }
+
+    /** Load a dictionary compiled by `to_bytes`. The checksum is verified and the graph is checked to be well formed,
+     * so a damaged file is an error rather than a dictionary that misbehaves.
+     */
+    pub fn from_bytes(bytes: &[u8]) -> Result<DawgDictionary, Error> {
+        let bad = |reason| Error::BadDictionaryFile { reason };
+        if !DawgDictionary::is_compiled(bytes) {
+            return Err(bad("it doesn't start with the right magic bytes"));
+        }
+        if bytes.len() < DawgDictionary::MAGIC.len() + 12 {
+            return Err(bad("it's too short"));
+        }
+        let (contents, checksum) = bytes.split_at(bytes.len() - 8);
+        if fnv1a(contents).to_le_bytes() != checksum {
+            return Err(bad("the checksum doesn't match"));
+        }
+
+        let mut reader = ByteReader {
+            bytes: contents,
+            position: DawgDictionary::MAGIC.len(),
+        };
+        if reader.u32()? != DawgDictionary::FORMAT_VERSION {
+            return Err(bad("it was written by an unsupported version"));
+        }
+        let name = reader.text()?;
+        let letters = reader.text()?;
+        let alphabet =
+            Alphabet::new(name, letters).map_err(|_| bad("its alphabet has too many letters"))?;
+        let node_count = reader.u32()? as usize;
+        let edge_count = reader.u32()? as usize;
+        if node_count == 0 {
+            return Err(bad("it has no root node"));
+        }
+
+        let mut nodes = Vec::with_capacity(node_count.min(reader.remaining() / 8));
+        for _ in 0..node_count {
+            let node = DawgNode {
+                first_edge: reader.u32()?,
+                edge_count: reader.u16()?,
+                is_word: reader.u8()? != 0,
+            };
+            reader.u8()?;
+            if node.first_edge as usize + node.edge_count as usize > edge_count {
+                return Err(bad("a node's edges are out of range"));
+            }
+            nodes.push(node);
+        }
+        let mut edges = Vec::with_capacity(edge_count.min(reader.remaining() / 6));
+        for _ in 0..edge_count {
+            let edge = DawgEdge {
+                letter: reader.u16()?,
+                target: reader.u32()?,
+            };
+            if edge.letter as usize >= alphabet.len() || edge.target as usize >= node_count {
+                return Err(bad("an edge is out of range"));
+            }
+            edges.push(edge);
+        }
+        if reader.remaining() != 0 {
+            return Err(bad("it has extra bytes at the end"));
+        }
+
+        let dict = DawgDictionary {
+            alphabet,
+            nodes,
+            edges,
+        };
+        // Stepping relies on each node's edges being sorted by letter
+        for node in 0..node_count as u32 {
+            if dict
+                .edges(node)
+                .windows(2)
+                .any(|e| e[0].letter >= e[1].letter)
+            {
+                return Err(bad("a node's edges aren't sorted"));
+            }
+        }
+        Ok(dict)
+    }
+
+    /** Write the compiled dictionary to a file.
+     */
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        Ok(fs::write(path, self.to_bytes())?)
+    }
+
+    /** Load a compiled dictionary from a file written by `save`.
+     */
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<DawgDictionary, Error> {
+        DawgDictionary::from_bytes(&fs::read(path)?)
+    }
+
+    // The edges leaving a node, sorted by letter
+    fn edges(&self, node: u32) -> &[DawgEdge] {
+        let node = &self.nodes[node as usize];
+        let first = node.first_edge as usize;
+        &self.edges[first..first + node.edge_count as usize]
+    }
+}
+
+/** Reads little-endian numbers and strings out of a compiled dictionary, failing if it runs out of bytes.
+ */
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], Error> {
+        let end = self.position.saturating_add(count);
+        let taken = self
+            .bytes
+            .get(self.position..end)
+            .ok_or(Error::BadDictionaryFile {
+                reason: "it ends too soon",
+            })?;
+        self.position = end;
+        Ok(taken)
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn text(&mut self) -> Result<&'a str, Error> {
+        let length = self.u32()? as usize;
+        std::str::from_utf8(self.take(length)?).map_err(|_| Error::BadDictionaryFile {
+            reason: "its alphabet isn't valid UTF-8",
+        })
+    }
+}
+
+// The 64-bit FNV-1a hash, used to check compiled dictionaries haven't been damaged
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+struct BuildNode {
+    is_word: bool,
+    edges: Vec<DawgEdge>,
+}
+
+/** The state of a DawgDictionary while it's being built.
+ */
+struct DawgBuilder {
+    nodes: Vec<BuildNode>,
+    // Every node that has been checked against the others, so identical nodes can be found
+    register: HashMap<BuildNode, u32>,
+    // The edges along the most recently added word that haven't been checked yet: (parent, letter, child)
+    unchecked: Vec<(u32, u16, u32)>,
+}
+impl DawgBuilder {
+    /** Check the unchecked nodes deeper than `depth`, from the bottom up, merging each into an identical registered
+     * node if there is one.
+     */
+    fn minimize(&mut self, depth: usize) {
+        while self.unchecked.len() > depth {
+            let (parent, letter, child) = self.unchecked.pop().unwrap();
+            match self.register.get(&self.nodes[child as usize]) {
+                Some(&existing) => {
+                    let edge = self.nodes[parent as usize].edges.last_mut().unwrap();
+                    debug_assert_eq!(edge.letter, letter);
+                    edge.target = existing;
+                    // Everything below the child is registered by now, so nothing built after the child is still
+                    // unregistered and the child is always the newest node
+                    debug_assert_eq!(child as usize, self.nodes.len() - 1);
+                    self.nodes.pop();
+                }
+                None => {
+                    self.register
+                        .insert(self.nodes[child as usize].clone(), child);
                 }
             }
         }
-        dict
+    }
+}
+
+/** Read every line of a file. Bytes that aren't valid UTF-8 are replaced rather than failing the whole file, so that
+ * the line they're on can be skipped as invalid.
+ */
+pub fn read_lines<P: AsRef<Path>>(path: P) -> Result<Vec<String>, Error> {
+    let bytes = fs::read(path)?;
+    Ok(String::from_utf8_lossy(&bytes)
+        .lines()
+        .map(String::from)
+        .collect())
+}
+
+/** Read a word list file, normalize it with the given options, and hand the words and alphabet to `build` to make a
+ * dictionary out of. Returns the dictionary along with a report of which lines were skipped.
+ */
+pub fn load_with<P, D, F>(
+    path: P,
+    options: &NormalizeOptions,
+    build: F,
+) -> Result<(D, LoadReport), Error>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&[String], Alphabet) -> Result<D, Error>,
+{
+    let (words, report) = normalize(read_lines(path)?, options)?;
+    Ok((build(&words, options.alphabet.clone())?, report))
+}
+
+/** How to clean up a raw word list before building a dictionary out of it.
+ */
+#[derive(Clone, Debug)]
+pub struct NormalizeOptions {
+    /// The letters words may contain
+    pub alphabet: Alphabet,
+    /// Lowercase every word
+    pub case_fold: bool,
+    /// What to do with a word containing characters outside the alphabet (after case folding)
+    pub invalid_words: InvalidWords,
+    /// Drop repeated words
+    pub remove_duplicates: bool,
+}
+impl Default for NormalizeOptions {
+    fn default() -> NormalizeOptions {
+        NormalizeOptions {
+            alphabet: Alphabet::english(),
+            case_fold: true,
+            invalid_words: InvalidWords::Skip,
+            remove_duplicates: true,
+        }
+    }
+}
+
+/** What to do when a word list contains a word that a dictionary can't store.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidWords {
+    /// Leave the word out and count it in the LoadReport
+    Skip,
+    /// Fail with Error::InvalidCharacter
+    Reject,
+}
+
+/** What happened to each line of a word list during normalization.
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    /// Words that made it into the list
+    pub accepted: usize,
+    /// Blank lines
+    pub empty: usize,
+    /// Words with characters outside the alphabet
+    pub invalid: usize,
+    /// Words that had already been seen
+    pub duplicates: usize,
+}
+impl LoadReport {
+    pub fn skipped(&self) -> usize {
+        self.empty + self.invalid + self.duplicates
+    }
+}
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} words loaded, {} skipped ({} empty, {} invalid, {} duplicates)",
+            self.accepted,
+            self.skipped(),
+            self.empty,
+            self.invalid,
+            self.duplicates
+        )
+    }
+}
+
+/** Clean up a raw word list: trim surrounding whitespace (including the \r of CRLF line endings), optionally lowercase
+ * and remove duplicates, and skip or reject words with characters outside the alphabet. Returns the cleaned-up words in their
+ * original order, along with a report of what was skipped.
+ */
+pub fn normalize<I, S>(
+    words: I,
+    options: &NormalizeOptions,
+) -> Result<(Vec<String>, LoadReport), Error>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut report = LoadReport::default();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut normalized: Vec<String> = Vec::new();
+    for word in words {
+        let word = word.as_ref().trim();
+        if word.is_empty() {
+            report.empty += 1;
+            continue;
+        }
+        let word = if options.case_fold {
+            word.to_lowercase()
+        } else {
+            String::from(word)
+        };
+        if let Some(character) = word.chars().find(|&c| !options.alphabet.contains(c)) {
+            match options.invalid_words {
+                InvalidWords::Skip => {
+                    report.invalid += 1;
+                    continue;
+                }
+                InvalidWords::Reject => return Err(Error::InvalidCharacter { word, character }),
+            }
+        }
+        if options.remove_duplicates && !seen.insert(word.clone()) {
+            report.duplicates += 1;
+            continue;
+        }
+        report.accepted += 1;
+        normalized.push(word);
+    }
+    Ok((normalized, report))
+}
+
+// TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words() -> Vec<String> {
+        ["a", "an", "and", "ant", "bar", "barter", "bartered"]
+            .iter()
+            .map(|w| String::from(*w))
+            .collect()
+    }
+
+    #[test]
+    fn test_hash_map_dictionary() {
+        let dict = HashMapDictionary::new(&words());
+        assert_eq!(dict.len(), 7);
+        assert_eq!(Dictionary::is_word(&dict, "a"), (true, false));
+        assert_eq!(Dictionary::is_word(&dict, "and"), (true, true));
+        assert_eq!(Dictionary::is_word(&dict, "bart"), (false, false));
+        assert_eq!(Dictionary::is_word(&dict, "bartered"), (true, true));
+        assert_eq!(Dictionary::is_word(&dict, "bb"), (false, true));
+        assert_eq!(dict.payload("ant"), Some(3));
+        assert_eq!(dict.payload("ants"), None);
+    }
+
+    #[test]
+    fn test_dictionaries_agree() {
+        // An empty word in the list shouldn't make the empty string a word in either dictionary
+        let mut list = words();
+        list.push(String::new());
+        let graph = GraphDictionary::new(&list).unwrap();
+        let hash_map = HashMapDictionary::new(&list);
+        assert_eq!(hash_map.len(), 7);
+        for candidate in [
+            "", "a", "an", "ab", "anda", "b", "ba", "bar", "barte", "barter", "bartered", "z",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                Dictionary::is_word(&graph, candidate),
+                Dictionary::is_word(&hash_map, candidate),
+                "{}",
+                candidate
+            );
+        }
+    }
+
+    #[test]
+    fn test_graph_layout() {
+        let mut list = words();
+        list.reverse();
+        list.push(String::from("an"));
+        let dict = GraphDictionary::new(&list).unwrap();
+        // The root plus one node per distinct prefix: a n d t b a r t e r e d
+        assert_eq!(dict.node_count(), 13);
+        assert_eq!(
+            GraphDictionary::to_string(&dict)
+                .lines()
+                .take(4)
+                .collect::<Vec<&str>>(),
+            vec![
+                "a: is word -> ",
+                "- n: is word -> ",
+                "- - d: is word -> ",
+                "- - t: is word -> "
+            ]
+        );
+        assert_eq!(Dictionary::is_word(&dict, "bartered"), (true, true));
+
+        let empty = GraphDictionary::new(&[]).unwrap();
+        assert_eq!(empty.node_count(), 1);
+        assert_eq!(
+            dict.memory_usage() - empty.memory_usage(),
+            12 * std::mem::size_of::<Node>()
+        );
+        assert_eq!(Dictionary::is_word(&empty, "a"), (false, true));
+    }
+
+    #[test]
+    fn test_dawg() {
+        let list: Vec<String> = ["tops", "tap", "top", "taps", "top"]
+            .iter()
+            .map(|w| String::from(*w))
+            .collect();
+        let dawg = DawgDictionary::new(&list).unwrap();
+        // root, t, ta/to, tap/top, taps/tops
+        assert_eq!(dawg.node_count(), 5);
+        assert_eq!(GraphDictionary::new(&list).unwrap().node_count(), 8);
+        assert_eq!(Dictionary::is_word(&dawg, "top"), (true, false));
+        assert_eq!(Dictionary::is_word(&dawg, "taps"), (true, true));
+        assert_eq!(Dictionary::is_word(&dawg, "to"), (false, false));
+        assert_eq!(Dictionary::is_word(&dawg, "tip"), (false, true));
+
+        // A word that is a prefix of another doesn't get merged with a word that isn't
+        let dawg = DawgDictionary::new(&words()).unwrap();
+        let graph = GraphDictionary::new(&words()).unwrap();
+        assert!(dawg.node_count() < graph.node_count());
+        for candidate in [
+            "a", "an", "and", "ant", "anted", "bar", "bart", "barter", "bartered", "d", "ered",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                Dictionary::is_word(&dawg, candidate),
+                Dictionary::is_word(&graph, candidate),
+                "{}",
+                candidate
+            );
+        }
+        assert!(DawgDictionary::new(&[String::from("ok?")]).is_err());
+    }
+
+    #[test]
+    fn test_compiled_dawg() {
+        let dawg = DawgDictionary::with_alphabet(&words(), Alphabet::german()).unwrap();
+        let bytes = dawg.to_bytes();
+        assert!(DawgDictionary::is_compiled(&bytes));
+        assert!(!DawgDictionary::is_compiled(b"aardvark\n"));
+
+        let loaded = DawgDictionary::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.alphabet(), &Alphabet::german());
+        assert_eq!(loaded.node_count(), dawg.node_count());
+        for candidate in ["a", "an", "and", "anda", "bart", "bartered", "z"].iter() {
+            assert_eq!(
+                Dictionary::is_word(&loaded, candidate),
+                Dictionary::is_word(&dawg, candidate),
+                "{}",
+                candidate
+            );
+        }
+
+        let reason = |bytes: &[u8]| match DawgDictionary::from_bytes(bytes) {
+            Err(Error::BadDictionaryFile { reason }) => reason,
+            other => panic!("expected a bad file, got {:?}", other),
+        };
+        assert_eq!(
+            reason(b"aardvark"),
+            "it doesn't start with the right magic bytes"
+        );
+        assert_eq!(reason(&bytes[..12]), "it's too short");
+        let mut damaged = bytes.clone();
+        damaged[30] ^= 1;
+        assert_eq!(reason(&damaged), "the checksum doesn't match");
+        // A file that is self-consistent but from a newer version
+        let mut newer = bytes[..bytes.len() - 8].to_vec();
+        newer[8] = 2;
+        let checksum = fnv1a(&newer);
+        newer.extend_from_slice(&checksum.to_le_bytes());
+        assert_eq!(reason(&newer), "it was written by an unsupported version");
+    }
+
+    #[test]
+    fn test_invalid_characters() {
+        let words = vec![String::from("fine"), String::from("don't")];
+        match GraphDictionary::new(&words) {
+            Err(Error::InvalidCharacter { word, character }) => {
+                assert_eq!(word, "don't");
+                assert_eq!(character, '\'');
+            }
+            other => panic!("expected an invalid character, got {:?}", other),
+        }
+        // Looking up characters outside of the dictionary is fine, they just aren't words
+        let dict = GraphDictionary::new(&words[..1]).unwrap();
+        assert_eq!(Dictionary::is_word(&dict, "FINE"), (false, true));
+        assert_eq!(Dictionary::is_word(&dict, "f\u{e9}"), (false, true));
+    }
+
+    #[test]
+    fn test_normalize() {
+        let raw = [
+            "Apple\r",
+            "  banana ",
+            "",
+            "don't",
+            "apple",
+            "CHERRY",
+            "co-op",
+            "\r",
+        ];
+        let (words, report) = normalize(raw.iter(), &NormalizeOptions::default()).unwrap();
+        assert_eq!(words, vec!["apple", "banana", "cherry"]);
+        assert_eq!(
+            report,
+            LoadReport {
+                accepted: 3,
+                empty: 2,
+                invalid: 2,
+                duplicates: 1
+            }
+        );
+        assert_eq!(report.skipped(), 5);
+
+        let options = NormalizeOptions {
+            case_fold: false,
+            remove_duplicates: false,
+            ..NormalizeOptions::default()
+        };
+        let (words, report) = normalize(raw.iter(), &options).unwrap();
+        assert_eq!(words, vec!["banana", "apple"]);
+        assert_eq!(report.invalid, 4);
+
+        let options = NormalizeOptions {
+            invalid_words: InvalidWords::Reject,
+            ..NormalizeOptions::default()
+        };
+        assert!(matches!(
+            normalize(raw.iter(), &options),
+            Err(Error::InvalidCharacter {
+                character: '\'',
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_load() {
+        let path = std::env::temp_dir().join(format!("words-{}.txt", std::process::id()));
+        fs::write(&path, "Bar\r\nbarter\n\nco-op\nbar\n").unwrap();
+        let (graph, report) = GraphDictionary::load(&path, &NormalizeOptions::default()).unwrap();
+        let (dawg, dawg_report) =
+            DawgDictionary::load(&path, &NormalizeOptions::default()).unwrap();
+        let (hash_map, _) = load_with(&path, &NormalizeOptions::default(), |words, _| {
+            Ok(HashMapDictionary::new(words))
+        })
+        .unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(report.accepted, 2);
+        assert_eq!(report.skipped(), 3);
+        assert_eq!(report, dawg_report);
+        assert_eq!(hash_map.len(), 2);
+        for candidate in ["bar", "bart", "barter", "co"].iter() {
+            assert_eq!(
+                Dictionary::is_word(&graph, candidate),
+                Dictionary::is_word(&dawg, candidate)
+            );
+        }
+        assert!(matches!(
+            GraphDictionary::from_file("no/such/words.txt"),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_other_alphabets() {
+        let raw = ["Straße", "STRASSE", "über", "niño"];
+        let options = NormalizeOptions {
+            alphabet: Alphabet::german(),
+            ..NormalizeOptions::default()
+        };
+        let (words, report) = normalize(raw.iter(), &options).unwrap();
+        assert_eq!(words, vec!["straße", "strasse", "über"]);
+        assert_eq!(report.invalid, 1);
+
+        let dict = GraphDictionary::with_alphabet(&words, Alphabet::german()).unwrap();
+        assert_eq!(Dictionary::is_word(&dict, "straße"), (true, true));
+        assert_eq!(Dictionary::is_word(&dict, "stra"), (false, false));
+        assert_eq!(Dictionary::is_word(&dict, "über"), (true, true));
+        assert!(GraphDictionary::new(&words).is_err());
     }
 }
@@ -0,0 +1,113 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+use crate::grid::ParseGridError;
+
+/** Everything that can go wrong while building dictionaries and grids or solving them.
+ */
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// A grid couldn't be parsed
+    ParseGrid(ParseGridError),
+    /// A word contains a character that the dictionary can't store
+    InvalidCharacter {
+        word: String,
+        character: char,
+    },
+    /// The grid has no tiles
+    EmptyGrid,
+    /// A row of the grid has a different number of tiles to the first row
+    RaggedGrid {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A tile in the grid has no letters on it
+    BlankTile {
+        row: usize,
+        col: usize,
+    },
+    /// A compiled dictionary file is damaged, or isn't a compiled dictionary at all
+    BadDictionaryFile {
+        reason: &'static str,
+    },
+    /// A tile in the grid has a letter that isn't in the alphabet being used
+    UnknownLetter {
+        row: usize,
+        col: usize,
+        letter: char,
+    },
+    /// An alphabet was given more than the 256 letters it can hold
+    AlphabetTooLarge {
+        letters: usize,
+    },
+    /// No generated board met the constraints before the budget ran out
+    NoBoardFound {
+        tried: usize,
+    },
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::ParseGrid(e) => write!(f, "{}", e),
+            Error::InvalidCharacter { word, character } => write!(
+                f,
+                "the word '{}' contains {:?}, which the dictionary can't store",
+                word, character
+            ),
+            Error::EmptyGrid => write!(f, "the grid has no tiles"),
+            Error::RaggedGrid {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} of the grid has {} tiles, but the first row has {}",
+                row, found, expected
+            ),
+            Error::BlankTile { row, col } => {
+                write!(f, "the tile at ({}, {}) has no letters on it", row, col)
+            }
+            Error::BadDictionaryFile { reason } => {
+                write!(f, "not a valid compiled dictionary: {}", reason)
+            }
+            Error::UnknownLetter { row, col, letter } => write!(
+                f,
+                "the tile at ({}, {}) has {:?}, which isn't in the alphabet",
+                row, col, letter
+            ),
+            Error::AlphabetTooLarge { letters } => write!(
+                f,
+                "an alphabet can hold at most 256 letters, but {} were given",
+                letters
+            ),
+            Error::NoBoardFound { tried } => {
+                write!(f, "none of the {} boards tried met the constraints", tried)
+            }
+        }
+    }
+}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::ParseGrid(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+impl From<ParseGridError> for Error {
+    fn from(e: ParseGridError) -> Error {
+        Error::ParseGrid(e)
+    }
+}
@@ -0,0 +1,582 @@
+use std::collections::HashMap;
+
+use crate::dictionary::Dictionary;
+use crate::grid::Grid;
+use crate::scoring::{self, Scorer};
+use crate::{Error, Solution, Solver, SolverConfig};
+
+/** The sixteen dice of classic 4x4 Boggle. A "q" face is played as "qu".
+ */
+pub const CLASSIC_DICE: [&str; 16] = [
+    "aaeegn", "abbjoo", "achops", "affkps", "aoottw", "cimotu", "deilrx", "delrvy", "distty",
+    "eeghnw", "eeinsu", "ehrtvw", "eiosst", "elrtty", "himnqu", "hlnnrz",
+];
+
+/** The twenty-five dice of 5x5 Big Boggle.
+ */
+pub const BIG_BOGGLE_DICE: [&str; 25] = [
+    "aaafrs", "aaeeee", "aafirs", "adennn", "aeeeem", "aeegmu", "aegmnn", "afirsy", "bjkqxz",
+    "ccenst", "ceiilt", "ceilpt", "ceipst", "ddhnot", "dhhlor", "dhlnor", "dhlnor", "eiiitt",
+    "emottt", "ensssu", "fiprsy", "gorrvw", "iprrry", "nootuw", "ooottu",
+];
+
+/** How often each letter appears in English text, in hundredths of a percent.
+ */
+pub const ENGLISH_FREQUENCIES: [(char, u32); 26] = [
+    ('e', 1270),
+    ('t', 906),
+    ('a', 817),
+    ('o', 751),
+    ('i', 697),
+    ('n', 675),
+    ('s', 633),
+    ('h', 609),
+    ('r', 599),
+    ('d', 425),
+    ('l', 403),
+    ('c', 278),
+    ('u', 276),
+    ('m', 241),
+    ('w', 236),
+    ('f', 223),
+    ('g', 202),
+    ('y', 197),
+    ('p', 193),
+    ('b', 129),
+    ('v', 98),
+    ('k', 77),
+    ('j', 15),
+    ('x', 15),
+    ('q', 10),
+    ('z', 7),
+];
+
+/** A small, seedable pseudo-random number generator (SplitMix64). It's nowhere near good enough for cryptography, but
+ * it's fast, and the same seed always gives the same boards.
+ */
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /** A number in 0..n. Panics if n is zero.
+     */
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "can't pick a number below zero");
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    /** Shuffle a slice in place (Fisher-Yates).
+     */
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+/** Where a Generator gets its letters from.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LetterSource {
+    /// Roll and shuffle the classic Boggle dice, for a 4x4 board
+    ClassicDice,
+    /// Roll and shuffle the Big Boggle dice, for a 5x5 board
+    BigBoggleDice,
+    /// Draw every tile independently, weighted by how common the letter is in English
+    EnglishFrequencies { width: usize, height: usize },
+}
+impl LetterSource {
+    pub fn width(&self) -> usize {
+        match self {
+            LetterSource::ClassicDice => 4,
+            LetterSource::BigBoggleDice => 5,
+            LetterSource::EnglishFrequencies { width, .. } => *width,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        match self {
+            LetterSource::ClassicDice => 4,
+            LetterSource::BigBoggleDice => 5,
+            LetterSource::EnglishFrequencies { height, .. } => *height,
+        }
+    }
+
+    fn dice(&self) -> Option<&'static [&'static str]> {
+        match self {
+            LetterSource::ClassicDice => Some(&CLASSIC_DICE),
+            LetterSource::BigBoggleDice => Some(&BIG_BOGGLE_DICE),
+            LetterSource::EnglishFrequencies { .. } => None,
+        }
+    }
+
+    // The letters for every tile of a new board, in row order
+    fn draw(&self, rng: &mut Rng) -> Draw {
+        match self.dice() {
+            Some(dice) => {
+                let mut order: Vec<usize> = (0..dice.len()).collect();
+                rng.shuffle(&mut order);
+                let letters = order.iter().map(|&die| roll(dice[die], rng)).collect();
+                Draw {
+                    dice: order,
+                    letters,
+                }
+            }
+            None => Draw {
+                dice: Vec::new(),
+                letters: (0..self.width() * self.height())
+                    .map(|_| draw_english(rng))
+                    .collect(),
+            },
+        }
+    }
+
+    /* Change a board a little: either reroll one tile, or (with dice) swap two of them around. Swapping keeps the
+     * same letters but changes which ones are next to each other.
+     */
+    fn mutate(&self, draw: &mut Draw, rng: &mut Rng) {
+        let tile = rng.below(draw.letters.len());
+        match self.dice() {
+            Some(dice) if rng.below(2) == 0 => {
+                draw.letters[tile] = roll(dice[draw.dice[tile]], rng);
+            }
+            Some(_) => {
+                let other = rng.below(draw.letters.len());
+                draw.letters.swap(tile, other);
+                draw.dice.swap(tile, other);
+            }
+            None => draw.letters[tile] = draw_english(rng),
+        }
+    }
+}
+
+/* The letters of a board, along with the die each one was rolled from (empty if there were no dice).
+ */
+#[derive(Clone, Debug)]
+struct Draw {
+    dice: Vec<usize>,
+    letters: Vec<char>,
+}
+
+fn roll(die: &str, rng: &mut Rng) -> char {
+    let faces: Vec<char> = die.chars().collect();
+    faces[rng.below(faces.len())]
+}
+
+fn draw_english(rng: &mut Rng) -> char {
+    let total: u32 = ENGLISH_FREQUENCIES.iter().map(|(_, weight)| weight).sum();
+    let mut pick = rng.below(total as usize) as u32;
+    for (letter, weight) in ENGLISH_FREQUENCIES.iter() {
+        if pick < *weight {
+            return *letter;
+        }
+        pick -= weight;
+    }
+    unreachable!("the pick is always below the total weight")
+}
+
+// A "q" on its own is nearly unplayable, so boards always get "qu" instead
+fn tile_letters(letter: char) -> String {
+    if letter == 'q' {
+        String::from("qu")
+    } else {
+        letter.to_string()
+    }
+}
+
+/** The value given to each letter on a generated board. A tile with several letters (like "qu") is worth the sum of
+ * its letters.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LetterValues {
+    values: HashMap<char, u32>,
+}
+impl LetterValues {
+    /** Every letter is worth nothing, until it's set.
+     */
+    pub fn new() -> LetterValues {
+        LetterValues {
+            values: HashMap::new(),
+        }
+    }
+
+    /** The values Wordament uses, from scoring::wordament_letter_value.
+     */
+    pub fn wordament() -> LetterValues {
+        let mut values = LetterValues::new();
+        for letter in 'a'..='z' {
+            values.set(letter, scoring::wordament_letter_value(letter));
+        }
+        values
+    }
+
+    pub fn set(&mut self, letter: char, value: u32) {
+        self.values.insert(letter, value);
+    }
+
+    pub fn get(&self, letter: char) -> u32 {
+        self.values.get(&letter).copied().unwrap_or(0)
+    }
+
+    pub fn value_of(&self, letters: &str) -> u32 {
+        letters.chars().map(|letter| self.get(letter)).sum()
+    }
+}
+impl Default for LetterValues {
+    fn default() -> LetterValues {
+        LetterValues::wordament()
+    }
+}
+
+/** Builds random boards. Two Generators made with the same source and seed produce the same sequence of boards.
+ */
+#[derive(Clone, Debug)]
+pub struct Generator {
+    source: LetterSource,
+    values: LetterValues,
+    rng: Rng,
+}
+impl Generator {
+    /** A generator that values its tiles like Wordament.
+     */
+    pub fn new(source: LetterSource, seed: u64) -> Generator {
+        Generator::with_values(source, LetterValues::wordament(), seed)
+    }
+
+    pub fn with_values(source: LetterSource, values: LetterValues, seed: u64) -> Generator {
+        Generator {
+            source,
+            values,
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn source(&self) -> LetterSource {
+        self.source
+    }
+
+    /** Build the next board.
+     */
+    pub fn generate(&mut self) -> Grid {
+        let draw = self.source.draw(&mut self.rng);
+        self.build(&draw)
+    }
+
+    /** Build boards until one meets the constraints, and return it along with its solution. Each board that doesn't
+     * is either changed a little (keeping the change if it gets no further from the constraints) or, once that stops
+     * helping, thrown away for a new one. Fails with NoBoardFound if `budget` boards are tried without success.
+     */
+    pub fn generate_matching<D, S>(
+        &mut self,
+        dictionary: &D,
+        scorer: S,
+        config: SolverConfig,
+        constraints: &Constraints,
+        budget: usize,
+    ) -> Result<GeneratedBoard, Error>
+    where
+        D: Dictionary,
+        S: Scorer + Clone + 'static,
+    {
+        let mut current: Option<(Draw, f64)> = None;
+        let mut stale = 0;
+        for tried in 1..=budget {
+            let draw = match &current {
+                Some((draw, _)) if stale < RESTART_AFTER => {
+                    let mut draw = draw.clone();
+                    self.source.mutate(&mut draw, &mut self.rng);
+                    draw
+                }
+                _ => {
+                    current = None;
+                    stale = 0;
+                    self.source.draw(&mut self.rng)
+                }
+            };
+            let grid = self.build(&draw);
+            let solution =
+                Solver::with_config(dictionary, grid.clone(), Box::new(scorer.clone()), config)?
+                    .solve_grid();
+            let shortfall = constraints.shortfall(&solution);
+            if shortfall == 0.0 {
+                return Ok(GeneratedBoard {
+                    grid,
+                    solution,
+                    boards_tried: tried,
+                });
+            }
+            match &current {
+                Some((_, best)) if shortfall > *best => stale += 1,
+                Some((_, best)) if shortfall == *best => {
+                    stale += 1;
+                    current = Some((draw, shortfall));
+                }
+                _ => {
+                    stale = 0;
+                    current = Some((draw, shortfall));
+                }
+            }
+        }
+        Err(Error::NoBoardFound { tried: budget })
+    }
+
+    fn build(&self, draw: &Draw) -> Grid {
+        let width = self.source.width();
+        let mut grid = Grid::init(width, self.source.height());
+        for (i, &letter) in draw.letters.iter().enumerate() {
+            let letters = tile_letters(letter);
+            let value = self.values.value_of(&letters);
+            grid.set(letters, value, i / width, i % width);
+        }
+        grid
+    }
+}
+
+// How many changes in a row can fail to help before a board is given up on
+const RESTART_AFTER: usize = 40;
+
+/** What makes a generated board good enough. Constraints left as None aren't checked.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Constraints {
+    /// The board must have at least this many words
+    pub min_words: Option<usize>,
+    /// The board's longest word must have at least this many letters
+    pub min_longest_word: Option<usize>,
+    /// The words on the board must add up to at least this score
+    pub target_score: Option<u32>,
+}
+impl Constraints {
+    pub fn is_empty(&self) -> bool {
+        self.min_words.is_none() && self.min_longest_word.is_none() && self.target_score.is_none()
+    }
+
+    pub fn satisfied_by(&self, solution: &Solution) -> bool {
+        self.shortfall(solution) == 0.0
+    }
+
+    /* How far a solution is from meeting the constraints: the fraction by which each one is missed, added up. Zero
+     * means every constraint is met.
+     */
+    fn shortfall(&self, solution: &Solution) -> f64 {
+        let missing = |target: Option<usize>, actual: usize| match target {
+            Some(target) if actual < target => (target - actual) as f64 / target as f64,
+            _ => 0.0,
+        };
+        missing(self.min_words, solution.words().len())
+            + missing(self.min_longest_word, longest_word(solution))
+            + missing(
+                self.target_score.map(|score| score as usize),
+                solution.score() as usize,
+            )
+    }
+}
+
+/** The number of letters in the longest word of a solution, or zero if it has none.
+ */
+pub fn longest_word(solution: &Solution) -> usize {
+    solution
+        .words()
+        .iter()
+        .map(|found| found.word.chars().count())
+        .max()
+        .unwrap_or(0)
+}
+
+/** A board that met its constraints.
+ */
+pub struct GeneratedBoard {
+    pub grid: Grid,
+    pub solution: Solution,
+    /// How many boards were solved to find this one, including itself
+    pub boards_tried: usize,
+}
+
+// TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::GraphDictionary;
+    use crate::scoring::WordamentScorer;
+    use crate::FoundWord;
+
+    fn tiles(grid: &Grid) -> Vec<String> {
+        let mut tiles = Vec::new();
+        for row in 0..grid.height {
+            for col in 0..grid.width {
+                tiles.push(grid.get(row, col).letters.clone());
+            }
+        }
+        tiles
+    }
+
+    #[test]
+    fn test_rng() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+        let mut rng = Rng::new(7);
+        assert!((0..1000).all(|_| rng.below(6) < 6));
+
+        let mut items: Vec<u32> = (0..20).collect();
+        rng.shuffle(&mut items);
+        let mut sorted = items.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..20).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn test_seeded() {
+        let first = Generator::new(LetterSource::ClassicDice, 1234).generate();
+        let again = Generator::new(LetterSource::ClassicDice, 1234).generate();
+        assert_eq!(first.to_string(), again.to_string());
+
+        let mut generator = Generator::new(LetterSource::ClassicDice, 1234);
+        generator.generate();
+        assert_ne!(generator.generate().to_string(), first.to_string());
+    }
+
+    #[test]
+    fn test_dice() {
+        let grid = Generator::new(LetterSource::ClassicDice, 5).generate();
+        assert_eq!((grid.width, grid.height), (4, 4));
+        for tile in tiles(&grid) {
+            let letter = tile.chars().next().unwrap();
+            assert!(CLASSIC_DICE.iter().any(|die| die.contains(letter)));
+        }
+
+        let grid = Generator::new(LetterSource::BigBoggleDice, 5).generate();
+        assert_eq!((grid.width, grid.height), (5, 5));
+    }
+
+    #[test]
+    fn test_english_frequencies() {
+        let source = LetterSource::EnglishFrequencies {
+            width: 6,
+            height: 3,
+        };
+        let grid = Generator::new(source, 99).generate();
+        assert_eq!((grid.width, grid.height), (6, 3));
+        for tile in tiles(&grid) {
+            assert!(
+                tile == "qu" || (tile.len() == 1 && tile.chars().all(|c| c.is_ascii_lowercase()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_values() {
+        let mut values = LetterValues::wordament();
+        assert_eq!(values.value_of("qu"), 12);
+        values.set('e', 3);
+        let mut generator = Generator::with_values(LetterSource::ClassicDice, values.clone(), 3);
+        let grid = generator.generate();
+        for row in 0..4 {
+            for col in 0..4 {
+                let cell = grid.get(row, col);
+                assert_eq!(cell.value, values.value_of(&cell.letters));
+            }
+        }
+    }
+
+    #[test]
+    fn test_constraints() {
+        let solution = Solution::new(vec![
+            FoundWord::new(String::from("cat"), vec![], 6),
+            FoundWord::new(String::from("coats"), vec![], 15),
+        ]);
+        assert_eq!(longest_word(&solution), 5);
+        assert!(Constraints::default().is_empty());
+        assert!(Constraints::default().satisfied_by(&solution));
+        let constraints = Constraints {
+            min_words: Some(2),
+            min_longest_word: Some(5),
+            target_score: Some(21),
+        };
+        assert!(!constraints.is_empty());
+        assert!(constraints.satisfied_by(&solution));
+        let too_many = Constraints {
+            min_words: Some(4),
+            ..constraints
+        };
+        assert!(!too_many.satisfied_by(&solution));
+        assert_eq!(too_many.shortfall(&solution), 0.5);
+        let too_long = Constraints {
+            min_longest_word: Some(6),
+            target_score: Some(22),
+            ..constraints
+        };
+        assert!(!too_long.satisfied_by(&solution));
+    }
+
+    #[test]
+    fn test_generate_matching() {
+        let words: Vec<String> = [
+            "at", "eat", "tea", "ate", "seat", "east", "stone", "notes", "tones",
+        ]
+        .iter()
+        .map(|w| String::from(*w))
+        .collect();
+        let dictionary = GraphDictionary::new(&words).unwrap();
+        let constraints = Constraints {
+            min_words: Some(3),
+            min_longest_word: Some(4),
+            target_score: None,
+        };
+        let source = LetterSource::EnglishFrequencies {
+            width: 4,
+            height: 4,
+        };
+        let mut generator = Generator::new(source, 11);
+        let board = generator
+            .generate_matching(
+                &dictionary,
+                WordamentScorer,
+                SolverConfig::wordament(),
+                &constraints,
+                5000,
+            )
+            .unwrap();
+        assert!(constraints.satisfied_by(&board.solution));
+        assert!(board.boards_tried <= 5000);
+        let solution = Solver::with_config(
+            &dictionary,
+            board.grid,
+            Box::new(WordamentScorer),
+            SolverConfig::wordament(),
+        )
+        .unwrap()
+        .solve_grid();
+        assert_eq!(solution.words().len(), board.solution.words().len());
+
+        let impossible = Constraints {
+            min_longest_word: Some(6),
+            ..constraints
+        };
+        let result = generator.generate_matching(
+            &dictionary,
+            WordamentScorer,
+            SolverConfig::wordament(),
+            &impossible,
+            50,
+        );
+        assert!(matches!(result, Err(Error::NoBoardFound { tried: 50 })));
+    }
+}
@@ -1,9 +1,21 @@
+use std::error;
 use std::fmt;
+use std::fs;
 use std::option::Option;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::alphabet::Alphabet;
+use crate::error::Error;
+use crate::scoring;
+
 /* This represents the grid of letters to pull words out of. Things to think about going forward:
  * - Is a Vec<Vec<Cell>> the most efficient data structure to use here? What would be better?
  */
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Grid {
+    #[cfg_attr(feature = "serde", serde(rename = "rows"))]
     cells: Vec<Vec<Cell>>,
     pub width: usize,
     pub height: usize,
@@ -19,15 +31,72 @@ impl Grid {
         }
     }
 
-    /** Update the value in a cell.
+    /** Build a Grid out of rows of cells. Every row must have the same number of cells, and there must be at least one.
+     */
+    pub fn from_rows(rows: Vec<Vec<Cell>>) -> Result<Grid, Error> {
+        let width = rows.first().map_or(0, Vec::len);
+        if width == 0 {
+            return Err(Error::EmptyGrid);
+        }
+        if let Some((row, cells)) = rows.iter().enumerate().find(|(_, r)| r.len() != width) {
+            return Err(Error::RaggedGrid {
+                row,
+                expected: width,
+                found: cells.len(),
+            });
+        }
+        Ok(Grid {
+            width,
+            height: rows.len(),
+            cells: rows,
+        })
+    }
+
+    /** Update the value in a cell. A cell can hold a single letter (`'a'`) or several (`"qu"`).
      */
-    pub fn set(&mut self, letter: char, value: u32, row: usize, col: usize) {
-        self.cells[row][col].letter = letter;
+    pub fn set<S: Into<String>>(&mut self, letters: S, value: u32, row: usize, col: usize) {
+        self.cells[row][col].letters = letters.into();
         self.cells[row][col].value = value;
     }
 
-    pub fn get(&self, row: usize, col: usize) -> Cell {
-        self.cells[row][col]
+    /** Give a cell other letters that it can be played as, instead of the letters it was set to.
+     */
+    pub fn set_alternatives(&mut self, alternatives: Vec<String>, row: usize, col: usize) {
+        self.cells[row][col].alternatives = alternatives;
+    }
+
+    /** Restrict where in a word a cell may be used.
+     */
+    pub fn set_placement(&mut self, placement: Placement, row: usize, col: usize) {
+        self.cells[row][col].placement = placement;
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> &Cell {
+        &self.cells[row][col]
+    }
+
+    /** Check that every letter on every tile (including alternatives) is part of an alphabet, so that a grid can't
+     * quietly contain tiles no word will ever use.
+     */
+    pub fn check_alphabet(&self, alphabet: &Alphabet) -> Result<(), Error> {
+        for (row, cells) in self.cells.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                if let Some(letter) = cell
+                    .choices()
+                    .flat_map(str::chars)
+                    .find(|&c| !alphabet.contains(c))
+                {
+                    return Err(Error::UnknownLetter { row, col, letter });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /** Read a Grid from a text file. See the FromStr implementation for the format.
+     */
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Grid, Error> {
+        Ok(fs::read_to_string(path)?.parse()?)
     }
 
     /** Returns a slice of string slices that each represent a neighbor of the current letter.
@@ -35,50 +104,50 @@ impl Grid {
      * [3]   (r,c)  [4]
      * [5]   [6]    [7]
      */
-    pub fn find_neighbors(&self, row: usize, col: usize) -> [Option<Cell>; 8] {
+    pub fn find_neighbors(&self, row: usize, col: usize) -> [Option<&Cell>; 8] {
         let left_bound = col > 0;
         let upper_bound = row > 0;
         let right_bound = col < self.width - 1;
         let bottom_bound = row < self.height - 1;
 
-        let possible_neighbors: [Option<Cell>; 8] = [
+        let possible_neighbors: [Option<&Cell>; 8] = [
             if upper_bound && left_bound {
-                Some(self.cells[row - 1][col - 1])
+                Some(&self.cells[row - 1][col - 1])
             } else {
                 None
             },
             if upper_bound {
-                Some(self.cells[row - 1][col])
+                Some(&self.cells[row - 1][col])
             } else {
                 None
             },
             if upper_bound && right_bound {
-                Some(self.cells[row - 1][col + 1])
+                Some(&self.cells[row - 1][col + 1])
             } else {
                 None
             },
             if left_bound {
-                Some(self.cells[row][col - 1])
+                Some(&self.cells[row][col - 1])
             } else {
                 None
             },
             if right_bound {
-                Some(self.cells[row][col + 1])
+                Some(&self.cells[row][col + 1])
             } else {
                 None
             },
             if bottom_bound && left_bound {
-                Some(self.cells[row + 1][col - 1])
+                Some(&self.cells[row + 1][col - 1])
             } else {
                 None
             },
             if bottom_bound {
-                Some(self.cells[row + 1][col])
+                Some(&self.cells[row + 1][col])
             } else {
                 None
             },
             if bottom_bound && right_bound {
-                Some(self.cells[row + 1][col + 1])
+                Some(&self.cells[row + 1][col + 1])
             } else {
                 None
             },
@@ -90,45 +159,245 @@ impl Grid {
 impl fmt::Debug for Grid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for row in &self.cells {
-            let res: fmt::Result = write!(f, "| ");
-            if res.is_err() {
-                return res;
-            }
+            write!(f, "| ")?;
             for cell in row {
-                let res: fmt::Result = write!(f, "{:?} | ", cell);
-                if res.is_err() {
-                    return res;
-                }
+                write!(f, "{:?} | ", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/** Write the grid in the format FromStr reads, with every tile's value spelled out (e.g. `a:2 re-:5` on each line), so
+ * that it can be saved and read back in.
+ */
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.cells {
+            let tiles: Vec<String> = row
+                .iter()
+                .map(|cell| format!("{}:{}", cell.label(None), cell.value))
+                .collect();
+            writeln!(f, "{}", tiles.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/** Grids are written one row per line. Tiles can either be separated by pipes, as in
+ * ```text
+ * | a | d | o |
+ * | b | s | n |
+ * ```
+ * or by whitespace, as in
+ * ```text
+ * a d o
+ * b s n
+ * ```
+ * Each tile is one or more letters (e.g. `a` or `qu`), optionally followed by its value (e.g. `a:2`). Tiles without a
+ * value are given the value Wordament normally uses for their letters. A trailing dash marks a tile that may only
+ * start a word (`re-`), and a leading dash marks one that may only end a word (`-ing`). Tiles that can be played as
+ * one of several letters separate them with slashes (`a/e`). Letters are lowercased, and blank lines are ignored.
+ */
+impl FromStr for Grid {
+    type Err = ParseGridError;
+
+    fn from_str(s: &str) -> Result<Grid, ParseGridError> {
+        let mut cells: Vec<Vec<Cell>> = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
             }
-            let res: fmt::Result = write!(f, "\n");
-            if res.is_err() {
-                return res;
+            let tokens: Vec<&str> = if let Some(line) = line.strip_prefix('|') {
+                // Only the outer pipes are optional; an empty tile between two pipes is an error
+                let line = line.strip_suffix('|').unwrap_or(line);
+                line.split('|').map(|t| t.trim()).collect()
+            } else {
+                line.split_whitespace().collect()
+            };
+            let row = tokens
+                .iter()
+                .map(|token| parse_cell(token, line_number))
+                .collect::<Result<Vec<Cell>, ParseGridError>>()?;
+            if let Some(first) = cells.first() {
+                if row.len() != first.len() {
+                    return Err(ParseGridError::RaggedRow {
+                        line: line_number,
+                        expected: first.len(),
+                        found: row.len(),
+                    });
+                }
             }
+            cells.push(row);
+        }
+        if cells.is_empty() || cells[0].is_empty() {
+            return Err(ParseGridError::Empty);
+        }
+        Ok(Grid {
+            width: cells[0].len(),
+            height: cells.len(),
+            cells,
+        })
+    }
+}
+
+// Parse a single tile of the form `letters` or `letters:value`
+fn parse_cell(token: &str, line: usize) -> Result<Cell, ParseGridError> {
+    let bad_token = |reason: &'static str| ParseGridError::BadToken {
+        line,
+        token: String::from(token),
+        reason,
+    };
+    let (letters, value) = match token.find(':') {
+        None => (token, None),
+        Some(i) => (&token[..i], Some(&token[i + 1..])),
+    };
+    let (letters, placement) = if let Some(l) = letters.strip_suffix('-') {
+        (l, Placement::StartOnly)
+    } else if let Some(l) = letters.strip_prefix('-') {
+        (l, Placement::EndOnly)
+    } else {
+        (letters, Placement::Anywhere)
+    };
+    let mut choices: Vec<String> = Vec::new();
+    for choice in letters.split('/') {
+        if choice.is_empty() {
+            return Err(bad_token("missing a letter"));
+        }
+        if !choice.chars().all(char::is_alphabetic) {
+            return Err(bad_token("a tile can only contain letters"));
+        }
+        choices.push(choice.to_lowercase());
+    }
+    let letters = choices.remove(0);
+    let value = match value {
+        None => letters.chars().map(scoring::wordament_letter_value).sum(),
+        Some(v) => v
+            .parse()
+            .map_err(|_| bad_token("the value must be a non-negative integer"))?,
+    };
+    Ok(Cell {
+        letters,
+        alternatives: choices,
+        value,
+        placement,
+    })
+}
+
+/** Everything that can go wrong when reading a Grid.
+ */
+#[derive(Debug)]
+pub enum ParseGridError {
+    /// There were no tiles at all
+    Empty,
+    /// A row had a different number of tiles to the first row
+    RaggedRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A tile couldn't be understood
+    BadToken {
+        line: usize,
+        token: String,
+        reason: &'static str,
+    },
+}
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseGridError::Empty => write!(f, "the grid has no tiles"),
+            ParseGridError::RaggedRow {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {} has {} tiles, but the rows above have {}",
+                line, found, expected
+            ),
+            ParseGridError::BadToken {
+                line,
+                token,
+                reason,
+            } => write!(f, "bad tile '{}' on line {}: {}", token, line, reason),
         }
-        write!(f, "")
     }
 }
+impl error::Error for ParseGridError {}
 
-#[derive(Clone, Copy)]
+/** A tile in the grid. Most tiles hold a single letter, but some (like "qu" or "th") hold several, which are always
+ * used together. Either/or tiles (like "a/e") can be played as any one of their choices.
+ */
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Cell {
-    pub letter: char,
+    pub letters: String,
+    /// Other letters the tile can be played as, for either/or tiles
+    pub alternatives: Vec<String>,
     pub value: u32,
+    pub placement: Placement,
+}
+impl Cell {
+    /** Every way this tile can be played: its letters first, followed by any alternatives.
+     */
+    pub fn choices(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.letters.as_str()).chain(self.alternatives.iter().map(String::as_str))
+    }
+
+    /** The letters played for a given choice, or None if the tile doesn't have that many choices.
+     */
+    pub fn choice(&self, choice: usize) -> Option<&str> {
+        self.choices().nth(choice)
+    }
+
+    /** The tile's letters, written the way grids are parsed: "re-", "-ing" or "a/e". If a choice is given, only its
+     * letters are shown.
+     */
+    pub fn label(&self, choice: Option<usize>) -> String {
+        let letters = match choice {
+            Some(choice) => String::from(self.choice(choice).unwrap_or("?")),
+            None => self.choices().collect::<Vec<&str>>().join("/"),
+        };
+        match self.placement {
+            Placement::Anywhere => letters,
+            Placement::StartOnly => format!("{}-", letters),
+            Placement::EndOnly => format!("-{}", letters),
+        }
+    }
 }
 impl Default for Cell {
     fn default() -> Cell {
         Cell {
-            letter: ' ',
+            letters: String::from(" "),
+            alternatives: Vec::new(),
             value: 0,
+            placement: Placement::Anywhere,
         }
     }
 }
 // Pretty-printing a cell
 impl fmt::Debug for Cell {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "({}: {})", self.letter, self.value)
+        write!(f, "({}: {})", self.label(None), self.value)
     }
 }
 
+/** Where in a word a cell may be used. Wordament has tiles like "re-", which can only start a word, and "-ing", which
+ * can only end one.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Placement {
+    Anywhere,
+    StartOnly,
+    EndOnly,
+}
+
 // TESTS
 #[cfg(test)]
 mod tests {
@@ -152,9 +421,9 @@ mod tests {
         g1.set('b', 11, 0, 1);
         g1.set('c', 12, 0, 2);
         g1.set('d', 13, 0, 3);
-        assert!(g1.get(0, 0).letter == 'a');
+        assert!(g1.get(0, 0).letters == "a");
         assert!(g1.get(0, 0).value == 10);
-        assert!(g1.get(0, 2).letter == 'c');
+        assert!(g1.get(0, 2).letters == "c");
         assert!(g1.get(0, 2).value == 12);
     }
 
@@ -186,14 +455,180 @@ mod tests {
         g.set('h', 7, 2, 1);
         g.set('i', 8, 2, 2);
 
-        let middle_neighbors: [Option<Cell>; 8] = g.find_neighbors(1, 1);
-        assert!(middle_neighbors[0].unwrap().letter == 'a');
-        assert!(middle_neighbors[1].unwrap().letter == 'b');
-        assert!(middle_neighbors[2].unwrap().letter == 'c');
-        assert!(middle_neighbors[3].unwrap().letter == 'd');
-        assert!(middle_neighbors[4].unwrap().letter == 'f');
-        assert!(middle_neighbors[5].unwrap().letter == 'g');
-        assert!(middle_neighbors[6].unwrap().letter == 'h');
-        assert!(middle_neighbors[7].unwrap().letter == 'i');
+        let middle_neighbors: [Option<&Cell>; 8] = g.find_neighbors(1, 1);
+        assert!(middle_neighbors[0].unwrap().letters == "a");
+        assert!(middle_neighbors[1].unwrap().letters == "b");
+        assert!(middle_neighbors[2].unwrap().letters == "c");
+        assert!(middle_neighbors[3].unwrap().letters == "d");
+        assert!(middle_neighbors[4].unwrap().letters == "f");
+        assert!(middle_neighbors[5].unwrap().letters == "g");
+        assert!(middle_neighbors[6].unwrap().letters == "h");
+        assert!(middle_neighbors[7].unwrap().letters == "i");
+    }
+
+    #[test]
+    fn test_parse_pipes() {
+        let g: Grid = "| a | d | o |\n| b | s | n |\n| m | u | c |\n"
+            .parse()
+            .unwrap();
+        assert_eq!(g.width, 3);
+        assert_eq!(g.height, 3);
+        assert!(g.get(0, 1).letters == "d");
+        assert!(g.get(0, 1).value == 3);
+        assert!(g.get(2, 2).letters == "c");
+    }
+
+    #[test]
+    fn test_parse_whitespace_and_values() {
+        let g: Grid = "\n  A:7 b\n\nc   d:12\n".parse().unwrap();
+        assert_eq!(g.width, 2);
+        assert_eq!(g.height, 2);
+        assert!(g.get(0, 0).letters == "a");
+        assert!(g.get(0, 0).value == 7);
+        assert!(g.get(0, 1).value == 5);
+        assert!(g.get(1, 1).letters == "d");
+        assert!(g.get(1, 1).value == 12);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!("".parse::<Grid>(), Err(ParseGridError::Empty)));
+        assert!(matches!(
+            "a b\nc".parse::<Grid>(),
+            Err(ParseGridError::RaggedRow {
+                line: 2,
+                expected: 2,
+                found: 1
+            })
+        ));
+        assert!(matches!(
+            "a b\nc 4".parse::<Grid>(),
+            Err(ParseGridError::BadToken { line: 2, .. })
+        ));
+        assert!(matches!(
+            "a b:x".parse::<Grid>(),
+            Err(ParseGridError::BadToken { line: 1, .. })
+        ));
+        assert!(matches!(
+            "| a | | b |\n| c | | d |".parse::<Grid>(),
+            Err(ParseGridError::BadToken {
+                line: 1,
+                reason: "missing a letter",
+                ..
+            })
+        ));
+        assert!(matches!(
+            "| a | b |\n| c | |".parse::<Grid>(),
+            Err(ParseGridError::BadToken {
+                line: 2,
+                reason: "missing a letter",
+                ..
+            })
+        ));
+        assert!(matches!(
+            Grid::from_file("no/such/grid.txt"),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_multi_letter_tiles() {
+        let g: Grid = "qu:9 a\nTH e:3".parse().unwrap();
+        assert!(g.get(0, 0).letters == "qu");
+        assert!(g.get(0, 0).value == 9);
+        assert!(g.get(1, 0).letters == "th");
+        assert!(g.get(1, 0).value == 6);
+        assert!(matches!(
+            "q-u a".parse::<Grid>(),
+            Err(ParseGridError::BadToken { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_placement_tiles() {
+        let g: Grid = "re-:5 a\n-ING b".parse().unwrap();
+        assert!(g.get(0, 0).letters == "re");
+        assert!(g.get(0, 0).value == 5);
+        assert!(g.get(0, 0).placement == Placement::StartOnly);
+        assert!(g.get(0, 1).placement == Placement::Anywhere);
+        assert!(g.get(1, 0).letters == "ing");
+        assert!(g.get(1, 0).placement == Placement::EndOnly);
+        assert!(g.get(1, 0).label(None) == "-ing");
+        assert!(format!("{:?}", g.get(0, 0)) == "(re-: 5)");
+        assert!(matches!(
+            "a -".parse::<Grid>(),
+            Err(ParseGridError::BadToken { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_either_or_tiles() {
+        let g: Grid = "a/E:3 b".parse().unwrap();
+        assert!(g.get(0, 0).letters == "a");
+        assert!(g.get(0, 0).alternatives == vec![String::from("e")]);
+        assert!(g.get(0, 0).value == 3);
+        assert!(g.get(0, 0).choice(1) == Some("e"));
+        assert!(g.get(0, 0).label(None) == "a/e");
+        assert!(g.get(0, 0).label(Some(1)) == "e");
+        assert!(g.get(0, 1).choices().count() == 1);
+        assert!(matches!(
+            "a/ b".parse::<Grid>(),
+            Err(ParseGridError::BadToken { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_from_rows() {
+        let cell = |letters: &str| Cell {
+            letters: String::from(letters),
+            ..Cell::default()
+        };
+        let g =
+            Grid::from_rows(vec![vec![cell("a"), cell("b")], vec![cell("c"), cell("d")]]).unwrap();
+        assert_eq!(g.width, 2);
+        assert_eq!(g.height, 2);
+        assert!(g.get(1, 0).letters == "c");
+        assert!(matches!(Grid::from_rows(vec![]), Err(Error::EmptyGrid)));
+        assert!(matches!(
+            Grid::from_rows(vec![vec![cell("a"), cell("b")], vec![cell("c")]]),
+            Err(Error::RaggedGrid {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn test_check_alphabet() {
+        let g: Grid = "É ñ\nß:4 a/ü".parse().unwrap();
+        assert!(g.get(0, 0).letters == "é");
+        assert!(g.get(0, 0).value == 1);
+        assert!(g.get(1, 0).value == 4);
+        assert!(matches!(
+            g.check_alphabet(&Alphabet::english()),
+            Err(Error::UnknownLetter {
+                row: 0,
+                col: 0,
+                letter: 'é'
+            })
+        ));
+        assert!(matches!(
+            g.check_alphabet(&Alphabet::german()),
+            Err(Error::UnknownLetter { letter: 'é', .. })
+        ));
+
+        let g: Grid = "ß:4 a/ü".parse().unwrap();
+        assert!(g.check_alphabet(&Alphabet::german()).is_ok());
+        assert!(g.check_alphabet(&Alphabet::english()).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        let text = "re-:5 a/e:1\n-ing:7 qu:9\n";
+        let g: Grid = text.parse().unwrap();
+        assert_eq!(g.to_string(), text);
+        let g: Grid = "| c | a |\n| t | s |".parse().unwrap();
+        assert_eq!(g.to_string(), "c:3 a:2\nt:2 s:2\n");
     }
 }
@@ -1,184 +1,869 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt;
+use std::hash::Hash;
 use std::ops::{Add, AddAssign};
 
+pub use error::Error;
+
+pub mod alphabet;
 pub mod dictionary;
+pub mod error;
+pub mod generator;
 pub mod grid;
+pub mod output;
+pub mod render;
+pub mod scoring;
 
 pub struct Solver<T: dictionary::Dictionary> {
     dictionary: T,
     grid: grid::Grid,
+    scorer: Box<dyn scoring::Scorer>,
+    config: SolverConfig,
+}
+
+/** Limits on the words a Solver reports, and how much it records about each. Lengths are counted in letters, not
+ * tiles, so a "qu" tile counts as two.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SolverConfig {
+    /// Shorter words are still searched through, but not reported
+    pub min_word_length: usize,
+    /// The search stops once a word reaches this length. None means there's no limit.
+    pub max_word_length: Option<usize>,
+    /// Keep every path to each word in FoundWord::other_paths, not just the best one
+    pub keep_other_paths: bool,
+}
+impl SolverConfig {
+    /** The limits Wordament plays by: every word has at least three letters.
+     */
+    pub fn wordament() -> SolverConfig {
+        SolverConfig {
+            min_word_length: 3,
+            max_word_length: None,
+            keep_other_paths: false,
+        }
+    }
+}
+impl Default for SolverConfig {
+    /** No limits: every word in the dictionary that can be made is reported.
+     */
+    fn default() -> SolverConfig {
+        SolverConfig {
+            min_word_length: 1,
+            max_word_length: None,
+            keep_other_paths: false,
+        }
+    }
 }
 
 impl<T: dictionary::Dictionary> Solver<T> {
-    pub fn new(dictionary: T, grid: grid::Grid) -> Self {
-        Solver::<T> { dictionary, grid }
+    /** Create a Solver that scores words the way Wordament does.
+     */
+    pub fn new(dictionary: T, grid: grid::Grid) -> Result<Self, Error> {
+        Solver::with_scorer(dictionary, grid, Box::new(scoring::WordamentScorer))
     }
+
+    /** Create a Solver with a particular way of scoring words. Fails if the grid has no tiles, or if any of its tiles
+     * have no letters on them.
+     */
+    pub fn with_scorer(
+        dictionary: T,
+        grid: grid::Grid,
+        scorer: Box<dyn scoring::Scorer>,
+    ) -> Result<Self, Error> {
+        Solver::with_config(dictionary, grid, scorer, SolverConfig::default())
+    }
+
+    /** Create a Solver with a particular way of scoring words and limits on the words it reports. Fails if the grid
+     * has no tiles, or if any of its tiles have no letters on them.
+     */
+    pub fn with_config(
+        dictionary: T,
+        grid: grid::Grid,
+        scorer: Box<dyn scoring::Scorer>,
+        config: SolverConfig,
+    ) -> Result<Self, Error> {
+        if grid.width == 0 || grid.height == 0 {
+            return Err(Error::EmptyGrid);
+        }
+        for row in 0..grid.height {
+            for col in 0..grid.width {
+                if grid.get(row, col).choices().any(|c| c.trim().is_empty()) {
+                    return Err(Error::BlankTile { row, col });
+                }
+            }
+        }
+        Ok(Solver::<T> {
+            dictionary,
+            grid,
+            scorer,
+            config,
+        })
+    }
+
     pub fn solve_grid(self) -> Solution {
-        let mut final_solution: Solution = Solution::default();
-        // TODO: this could probably be parallelized
+        let mut final_solution = self.empty_solution();
         for row in 0..self.grid.height {
             for col in 0..self.grid.width {
-                let start = &self.grid.get(row, col);
-                let s: Solution = self.find_words_from(
-                    row,
-                    col,
-                    format!("{}", start.letter).as_str(),
-                    start.value,
-                    &mut vec![vec![false; self.grid.width]; self.grid.height],
-                );
-                final_solution += s;
+                final_solution += self.solve_from(row, col);
             }
         }
         final_solution
     }
 
-    /* Starting at (row, col), find all words emanating from that letter.
+    // Whether adding `letters` to a word of `length` letters would take it past the maximum length
+    fn too_long(&self, length: usize, letters: &str) -> bool {
+        self.config
+            .max_word_length
+            .is_some_and(|max| length + letters.chars().count() > max)
+    }
+
+    fn empty_solution(&self) -> Solution {
+        if self.config.keep_other_paths {
+            Solution::with_other_paths()
+        } else {
+            Solution::default()
+        }
+    }
+
+    /* Find every word that starts at (row, col).
      */
-    fn find_words_from(
-        &self,
-        row: usize,
-        col: usize,
-        word_acc: &str,
-        score: u32,
+    fn solve_from(&self, row: usize, col: usize) -> Solution {
+        let mut soln = self.empty_solution();
+        let start = self.grid.get(row, col);
+        // End-only tiles can't begin a word
+        if start.placement == grid::Placement::EndOnly {
+            return soln;
+        }
+        let mut visited_cells = vec![vec![false; self.grid.width]; self.grid.height];
+        let root = self.dictionary.root();
+        for (choice, letters) in start.choices().enumerate() {
+            if self.too_long(0, letters) {
+                continue;
+            }
+            if let Some(cursor) = self.dictionary.step_letters(&root, letters) {
+                self.find_words_from(
+                    PathStep::new(row, col, choice),
+                    &cursor,
+                    &mut String::from(letters),
+                    &mut visited_cells,
+                    &mut Vec::new(),
+                    &mut soln,
+                );
+            }
+        }
+        soln
+    }
+
+    /* Starting at `step`, find all words emanating from that tile and add them to `soln`. `cursor` is the
+     * dictionary's position after stepping through `word_acc`, which is spelled out by the steps in `path` plus
+     * `step`. `word_acc` and `path` are restored to their original state before returning.
+     */
+    fn find_words_from<'a>(
+        &'a self,
+        step: PathStep,
+        cursor: &T::Cursor<'a>,
+        word_acc: &mut String,
         visited_cells: &mut Vec<Vec<bool>>,
-    ) -> Solution {
-        let mut soln = Solution::default();
+        path: &mut Vec<PathStep>,
+        soln: &mut Solution,
+    ) {
+        let (row, col) = (step.row, step.col);
         // This cell has now been visited
         visited_cells[row][col] = true;
-        // First, check if the current candidate is a word
-        let (is_word, is_terminal) = dictionary::Dictionary::is_word(&self.dictionary, word_acc);
-        if is_word {
-            soln.found(String::from(word_acc), score);
-        }
-        // If this word has no subsequent words, stop recursing
-        if !is_terminal {
-            let possible_neighbors: &[Option<grid::Cell>; 8] = &self.grid.find_neighbors(row, col);
+        path.push(step);
+        let length = word_acc.chars().count();
+        // First, check if the current candidate is a long enough word
+        if length >= self.config.min_word_length && self.dictionary.is_word_at(cursor) {
+            let tile_values: Vec<u32> = path
+                .iter()
+                .map(|s| self.grid.get(s.row, s.col).value)
+                .collect();
+            let score = self.scorer.score(word_acc, &tile_values);
+            soln.found(word_acc.clone(), path.clone(), score);
+        }
+        // If this word has no subsequent words, or this tile has to end the word, or the word can't get any longer,
+        // stop recursing
+        let is_last = self.grid.get(row, col).placement == grid::Placement::EndOnly
+            || self.config.max_word_length.is_some_and(|max| length >= max);
+        if !is_last && self.dictionary.has_children(cursor) {
+            let possible_neighbors: [Option<&grid::Cell>; 8] = self.grid.find_neighbors(row, col);
             let row_mvmts: [i32; 8] = [-1, -1, -1, 0, 0, 1, 1, 1];
             let col_mvmts: [i32; 8] = [-1, 0, 1, -1, 1, -1, 0, 1];
             for i in 0..8 {
-                match &possible_neighbors[i] {
-                    Some(l) => {
-                        // Make sure the neighbor hasn't been visited before
-                        let neighbor_row = (row as i32 + row_mvmts[i]) as usize;
-                        let neighbor_col = (col as i32 + col_mvmts[i]) as usize;
-                        if !visited_cells[neighbor_row][neighbor_col] {
-                            let w = format!("{}{}", word_acc, l.letter);
-                            let s = self.find_words_from(
-                                neighbor_row,
-                                neighbor_col,
-                                w.as_str(),
-                                score + l.value,
+                if let Some(l) = possible_neighbors[i] {
+                    // Make sure the neighbor hasn't been visited before
+                    let neighbor_row = (row as i32 + row_mvmts[i]) as usize;
+                    let neighbor_col = (col as i32 + col_mvmts[i]) as usize;
+                    // Start-only tiles can only be used as the first tile
+                    if visited_cells[neighbor_row][neighbor_col]
+                        || l.placement == grid::Placement::StartOnly
+                    {
+                        continue;
+                    }
+                    // Try every way the neighbor can be played, following those that some word continues with
+                    for (choice, letters) in l.choices().enumerate() {
+                        if self.too_long(length, letters) {
+                            continue;
+                        }
+                        if let Some(next) = self.dictionary.step_letters(cursor, letters) {
+                            let word_len = word_acc.len();
+                            word_acc.push_str(letters);
+                            self.find_words_from(
+                                PathStep::new(neighbor_row, neighbor_col, choice),
+                                &next,
+                                word_acc,
                                 visited_cells,
+                                path,
+                                soln,
                             );
-                            soln.add_soln(&s);
+                            word_acc.truncate(word_len);
                         }
                     }
-                    None => {}
                 }
             }
         }
-        soln
+        // Backtrack so that this cell can be used by other paths
+        path.pop();
+        visited_cells[row][col] = false;
     }
 }
 
-// Represents a solution for a Grid with a dictionary
+#[cfg(feature = "parallel")]
+impl<T: dictionary::Dictionary + Sync> Solver<T> {
+    /** Solve the grid using every core. Each starting cell is searched on rayon's thread pool, and the results are
+     * merged in the same order solve_grid uses, so both return the same Solution.
+     */
+    pub fn solve_grid_parallel(self) -> Solution {
+        use rayon::prelude::*;
+
+        let starts: Vec<(usize, usize)> = (0..self.grid.height)
+            .flat_map(|row| (0..self.grid.width).map(move |col| (row, col)))
+            .collect();
+        let solutions: Vec<Solution> = starts
+            .par_iter()
+            .map(|&(row, col)| self.solve_from(row, col))
+            .collect();
+        solutions
+            .into_iter()
+            .fold(self.empty_solution(), |acc, soln| acc + soln)
+    }
+}
+
+/** One tile in the path of a found word: its position in the grid, and which of the tile's choices was played (see
+ * Cell::choices). For most tiles there's only one choice, so `choice` is 0.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PathStep {
+    pub row: usize,
+    pub col: usize,
+    pub choice: usize,
+}
+impl PathStep {
+    pub fn new(row: usize, col: usize, choice: usize) -> PathStep {
+        PathStep { row, col, choice }
+    }
+}
+
+/** A single word found in a grid, along with the cells that spell it out (in order) and the score it earned.
+ */
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FoundWord {
+    pub word: String,
+    /// Every tile used to make the word, from the first letter to the last. If the word can be made in more than one
+    /// way, this is the highest-scoring path.
+    pub path: Vec<PathStep>,
+    pub score: u32,
+    /// Every other path that also spells out the word, if the Solution keeps them (see Solution::with_other_paths).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub other_paths: Vec<Vec<PathStep>>,
+}
+impl FoundWord {
+    pub fn new(word: String, path: Vec<PathStep>, score: u32) -> FoundWord {
+        FoundWord {
+            word,
+            path,
+            score,
+            other_paths: Vec::new(),
+        }
+    }
+
+    /** The (row, col) of the tile the word's path starts on, or None if the word has no path.
+     */
+    pub fn start(&self) -> Option<(usize, usize)> {
+        self.path.first().map(|s| (s.row, s.col))
+    }
+}
+
+/** Represents a solution for a Grid with a dictionary. Each distinct word is only listed (and scored) once, no
+ * matter how many paths lead to it. Only the best path to each word is kept, unless the Solution was made with
+ * with_other_paths.
+ */
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Solution {
-    words_found: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(rename = "words"))]
+    words_found: Vec<FoundWord>,
+    // Position of each word in words_found
+    #[cfg_attr(feature = "serde", serde(skip))]
+    index: HashMap<String, usize>,
     score: u32,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    keep_other_paths: bool,
 }
 impl Solution {
-    /** Form a solutuion with a list of words found, as well as a total score for the solution.
+    /** Form a solution from a list of words found. Repeated words are merged, and the total score is the sum of each
+     * distinct word's best score.
      */
-    pub fn new(words_found: Vec<String>, score: u32) -> Solution {
-        Solution { words_found, score }
+    pub fn new(words_found: Vec<FoundWord>) -> Solution {
+        let mut soln = Solution::default();
+        for found in words_found {
+            soln.merge(found);
+        }
+        soln
+    }
+
+    /** An empty solution that keeps every path to each word it's given, not just the best one.
+     */
+    pub fn with_other_paths() -> Solution {
+        Solution {
+            keep_other_paths: true,
+            ..Solution::default()
+        }
     }
 
     /**
-     * Add a word to this Solution
+     * Add a word to this Solution. If the word has already been found, the higher-scoring of the two paths is kept
+     * as the word's path, and the other is kept in its other_paths if this Solution keeps them.
      */
-    pub fn found(&mut self, word: String, score: u32) -> () {
-        self.words_found.push(word);
-        self.score += score;
+    pub fn found(&mut self, word: String, path: Vec<PathStep>, score: u32) {
+        self.merge(FoundWord::new(word, path, score));
+    }
+
+    fn merge(&mut self, mut found: FoundWord) {
+        match self.index.get(&found.word) {
+            None => {
+                if !self.keep_other_paths {
+                    found.other_paths.clear();
+                }
+                self.index
+                    .insert(found.word.clone(), self.words_found.len());
+                self.score = self.score.saturating_add(found.score);
+                self.words_found.push(found);
+            }
+            Some(&i) => {
+                let existing = &mut self.words_found[i];
+                if found.score > existing.score {
+                    self.score = (self.score - existing.score).saturating_add(found.score);
+                    existing.score = found.score;
+                    std::mem::swap(&mut existing.path, &mut found.path);
+                }
+                if self.keep_other_paths {
+                    existing.other_paths.push(found.path);
+                    existing.other_paths.append(&mut found.other_paths);
+                }
+            }
+        }
     }
 
     /**
      * Add another Solution to this one. The other Solution is not consumed.
      */
     pub fn add_soln(&mut self, other: &Self) {
-        let all_words = Vec::<String>::from(
-            [self.words_found.as_slice(), other.words_found.as_slice()].concat(),
-        );
-        self.words_found = all_words;
-        self.score += other.score;
+        for found in &other.words_found {
+            self.merge(found.clone());
+        }
     }
-}
 
-impl Default for Solution {
-    fn default() -> Solution {
-        Solution {
-            words_found: Vec::default(),
-            score: 0,
+    /** Every distinct word found, in the order it was first found.
+     */
+    pub fn words(&self) -> &[FoundWord] {
+        &self.words_found
+    }
+
+    pub fn get(&self, word: &str) -> Option<&FoundWord> {
+        self.index.get(word).map(|&i| &self.words_found[i])
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    /** Every distinct word found, in the given order.
+     */
+    pub fn sorted(&self, order: SortOrder) -> Vec<&FoundWord> {
+        let mut words: Vec<&FoundWord> = self.words_found.iter().collect();
+        match order {
+            SortOrder::Found => {}
+            SortOrder::Alphabetical => words.sort_by(|a, b| a.word.cmp(&b.word)),
+            SortOrder::Length => words.sort_by(|a, b| {
+                b.word
+                    .chars()
+                    .count()
+                    .cmp(&a.word.chars().count())
+                    .then_with(|| a.word.cmp(&b.word))
+            }),
+            SortOrder::Score => {
+                words.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.word.cmp(&b.word)))
+            }
+            SortOrder::StartCell => words.sort_by(|a, b| {
+                compare_starts(&a.start(), &b.start()).then_with(|| a.word.cmp(&b.word))
+            }),
+        }
+        words
+    }
+
+    /** Every distinct word found, grouped by the tile its best path starts on. Groups are listed row by row, followed
+     * by a group keyed None for any words without a path, and the words in each group are in the given order.
+     */
+    pub fn grouped_by_start(&self, order: SortOrder) -> StartGroups<'_> {
+        Solution::group(self.sorted(order), |w| w.start(), compare_starts)
+    }
+
+    /** Every distinct word found, grouped by its number of letters. The longest words come first, and the words in
+     * each group are in the given order.
+     */
+    pub fn grouped_by_length(&self, order: SortOrder) -> Vec<(usize, Vec<&FoundWord>)> {
+        Solution::group(
+            self.sorted(order),
+            |w| w.word.chars().count(),
+            |a, b| b.cmp(a),
+        )
+    }
+
+    // Split words into groups that share a key, keeping the words' order within each group
+    fn group<'a, K, F, C>(
+        words: Vec<&'a FoundWord>,
+        key: F,
+        compare: C,
+    ) -> Vec<(K, Vec<&'a FoundWord>)>
+    where
+        K: Eq + Hash,
+        F: Fn(&FoundWord) -> K,
+        C: Fn(&K, &K) -> Ordering,
+    {
+        let mut groups: HashMap<K, Vec<&'a FoundWord>> = HashMap::new();
+        for found in words {
+            groups.entry(key(found)).or_default().push(found);
         }
+        let mut groups: Vec<(K, Vec<&'a FoundWord>)> = groups.into_iter().collect();
+        groups.sort_by(|a, b| compare(&a.0, &b.0));
+        groups
+    }
+}
+
+/** Orders that the words in a Solution can be listed in. Ties are broken alphabetically.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    /// The order the solver found the words in
+    Found,
+    Alphabetical,
+    /// Longest words first
+    Length,
+    /// Highest-scoring words first
+    Score,
+    /// By the tile each word starts on, row by row (words without a path come last)
+    StartCell,
+}
+
+/** Words grouped by the tile their best path starts on, as Solution::grouped_by_start returns them. Words without a
+ * path are grouped under None.
+ */
+pub type StartGroups<'a> = Vec<(Option<(usize, usize)>, Vec<&'a FoundWord>)>;
+
+// Order start tiles row by row, with no start tile at all coming last
+fn compare_starts(a: &Option<(usize, usize)>, b: &Option<(usize, usize)>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
     }
 }
+
 impl fmt::Display for Solution {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let res: fmt::Result = write!(f, "score: {}, words: ", self.score);
-        if res.is_err() {
-            return res;
+        write!(f, "score: {}, words: ", self.score)?;
+        for found in &self.words_found {
+            write!(f, "{}, ", found.word)?;
         }
-
-        for word in &self.words_found {
-            let res: fmt::Result = write!(f, "{}, ", word);
-            if res.is_err() {
-                return res;
-            }
-        }
-        write!(f, "")
+        Ok(())
     }
 }
 
 impl Add for Solution {
     type Output = Self;
-    fn add(self, other: Self) -> Self {
-        let all_words = Vec::<String>::from(
-            [self.words_found.as_slice(), other.words_found.as_slice()].concat(),
-        );
-        Self {
-            words_found: all_words,
-            score: self.score + other.score,
-        }
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
     }
 }
 
 impl AddAssign for Solution {
     fn add_assign(&mut self, other: Self) {
-        let all_words = Vec::<String>::from(
-            [self.words_found.as_slice(), other.words_found.as_slice()].concat(),
-        );
-        *self = Self {
-            words_found: all_words,
-            score: self.score + other.score,
-        };
+        for found in other.words_found {
+            self.merge(found);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    fn word(w: &str, score: u32) -> FoundWord {
+        FoundWord::new(String::from(w), vec![PathStep::new(0, 0, 0)], score)
+    }
+
+    fn cells(path: &[PathStep]) -> Vec<(usize, usize)> {
+        path.iter().map(|s| (s.row, s.col)).collect()
+    }
+
+    fn word_list(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| String::from(*w)).collect()
+    }
+
+    // Solve a board, written the way Grid::from_str reads it, with a dictionary of the given words
+    fn solve(board: &str, words: &[&str]) -> Solution {
+        solve_with(board, words, SolverConfig::default())
+    }
+
+    fn solve_with(board: &str, words: &[&str], config: SolverConfig) -> Solution {
+        let grid: grid::Grid = board.parse().unwrap();
+        let dictionary = dictionary::GraphDictionary::new(&word_list(words)).unwrap();
+        Solver::with_config(dictionary, grid, Box::new(scoring::WordamentScorer), config)
+            .unwrap()
+            .solve_grid()
+    }
+
+    #[test]
     fn test_add() {
-        let s1 = Solution::new(vec![String::from("one"), String::from("two")], 8);
-        let s2 = Solution::new(vec![String::from("three"), String::from("four")], 10);
+        let s1 = Solution::new(vec![word("one", 3), word("two", 5)]);
+        let s2 = Solution::new(vec![word("three", 4), word("four", 6)]);
+        let s3 = s1 + s2;
+        assert!(s3.score() == 18);
+        let words: Vec<&str> = s3.words().iter().map(|w| w.word.as_str()).collect();
+        assert!(words == vec!["one", "two", "three", "four"]);
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let mut s1 = Solution::with_other_paths();
+        s1 += Solution::new(vec![word("one", 3), word("two", 5)]);
+        s1.found(String::from("one"), vec![PathStep::new(1, 1, 0)], 7);
+        s1.found(String::from("two"), vec![PathStep::new(1, 1, 0)], 2);
+        assert_eq!(s1.score(), 12);
+        assert_eq!(s1.words().len(), 2);
+        assert_eq!(cells(&s1.get("one").unwrap().path), vec![(1, 1)]);
+        assert_eq!(cells(&s1.get("one").unwrap().other_paths[0]), vec![(0, 0)]);
+        assert_eq!(cells(&s1.get("two").unwrap().path), vec![(0, 0)]);
+
+        let s2 = Solution::new(vec![word("two", 9), word("three", 4)]);
         let s3 = s1 + s2;
-        assert!(s3.score == 18);
-        assert!(
-            s3.words_found
-                == vec![
-                    String::from("one"),
-                    String::from("two"),
-                    String::from("three"),
-                    String::from("four")
-                ]
+        assert_eq!(s3.score(), 20);
+        assert_eq!(s3.get("two").unwrap().score, 9);
+        assert_eq!(s3.get("two").unwrap().other_paths.len(), 2);
+
+        // By default, only the best path is kept
+        let mut s4 = Solution::new(vec![word("one", 3)]);
+        s4.found(String::from("one"), vec![PathStep::new(1, 1, 0)], 7);
+        s4 += s3;
+        assert_eq!(cells(&s4.get("one").unwrap().path), vec![(1, 1)]);
+        assert!(s4.words().iter().all(|w| w.other_paths.is_empty()));
+
+        // Scores are capped instead of overflowing
+        let mut s5 = Solution::new(vec![word("one", u32::MAX), word("two", 5)]);
+        assert_eq!(s5.score(), u32::MAX);
+        s5.found(String::from("two"), vec![PathStep::new(1, 1, 0)], 9);
+        assert_eq!(s5.score(), u32::MAX);
+    }
+
+    #[test]
+    fn test_sorted() {
+        let s1 = Solution::new(vec![
+            word("bb", 9),
+            word("ccc", 3),
+            word("aaa", 3),
+            word("dddd", 1),
+        ]);
+        let order = |words: Vec<&FoundWord>| -> Vec<String> {
+            words.iter().map(|w| w.word.clone()).collect()
+        };
+        assert_eq!(
+            order(s1.sorted(SortOrder::Found)),
+            vec!["bb", "ccc", "aaa", "dddd"]
+        );
+        assert_eq!(
+            order(s1.sorted(SortOrder::Alphabetical)),
+            vec!["aaa", "bb", "ccc", "dddd"]
+        );
+        assert_eq!(
+            order(s1.sorted(SortOrder::Length)),
+            vec!["dddd", "aaa", "ccc", "bb"]
         );
+        assert_eq!(
+            order(s1.sorted(SortOrder::Score)),
+            vec!["bb", "aaa", "ccc", "dddd"]
+        );
+    }
+
+    #[test]
+    fn test_grouped() {
+        let at = |w: &str, score: u32, row: usize, col: usize| {
+            FoundWord::new(String::from(w), vec![PathStep::new(row, col, 0)], score)
+        };
+        let s1 = Solution::new(vec![
+            at("tab", 5, 1, 0),
+            at("bat", 3, 0, 1),
+            at("bats", 8, 0, 1),
+            at("abs", 4, 0, 0),
+            at("tabs", 9, 1, 0),
+            FoundWord::new(String::from("sat"), Vec::new(), 5),
+        ]);
+        let words = |words: &Vec<&FoundWord>| -> Vec<String> {
+            words.iter().map(|w| w.word.clone()).collect()
+        };
+        assert_eq!(
+            words(&s1.sorted(SortOrder::StartCell)),
+            vec!["abs", "bat", "bats", "tab", "tabs", "sat"]
+        );
+
+        // A word without a path isn't filed under any tile
+        let by_start = s1.grouped_by_start(SortOrder::Score);
+        let starts: Vec<Option<(usize, usize)>> = by_start.iter().map(|g| g.0).collect();
+        assert_eq!(starts, vec![Some((0, 0)), Some((0, 1)), Some((1, 0)), None]);
+        assert_eq!(words(&by_start[1].1), vec!["bats", "bat"]);
+        assert_eq!(words(&by_start[2].1), vec!["tabs", "tab"]);
+        assert_eq!(words(&by_start[3].1), vec!["sat"]);
+
+        let by_length = s1.grouped_by_length(SortOrder::Alphabetical);
+        assert_eq!(by_length.len(), 2);
+        assert_eq!(by_length[0].0, 4);
+        assert_eq!(words(&by_length[0].1), vec!["bats", "tabs"]);
+        assert_eq!(words(&by_length[1].1), vec!["abs", "bat", "sat", "tab"]);
+    }
+
+    #[test]
+    fn test_paths() {
+        let solution = solve("a:1 b:2\nc:3 d:4", &["bad", "bcd"]);
+
+        // Prefixes such as "b" and "ba" are not words in their own right
+        assert_eq!(solution.words().len(), 2);
+        let bad = solution.get("bad").unwrap();
+        assert_eq!(cells(&bad.path), vec![(0, 1), (0, 0), (1, 1)]);
+        assert_eq!(bad.score, 7);
+        // "bcd" is searched after "ba...", so the cells visited along that branch must be released on the way back
+        let bcd = solution.get("bcd").unwrap();
+        assert_eq!(cells(&bcd.path), vec![(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(solution.score(), 16);
+    }
+
+    #[test]
+    fn test_best_path() {
+        let solver = |config: SolverConfig| {
+            let mut grid = grid::Grid::init(2, 2);
+            grid.set('a', 1, 0, 0);
+            grid.set('a', 3, 0, 1);
+            grid.set('b', 2, 1, 0);
+            grid.set('c', 1, 1, 1);
+            let dictionary = dictionary::GraphDictionary::new(&[String::from("ab")]).unwrap();
+            let scorer = Box::new(scoring::WordamentScorer);
+            Solver::with_config(dictionary, grid, scorer, config).unwrap()
+        };
+        let solution = solver(SolverConfig::default()).solve_grid();
+        assert!(solution.get("ab").unwrap().other_paths.is_empty());
+
+        let config = SolverConfig {
+            keep_other_paths: true,
+            ..SolverConfig::default()
+        };
+        let solution = solver(config).solve_grid();
+        assert_eq!(solution.words().len(), 1);
+        let ab = solution.get("ab").unwrap();
+        assert_eq!(cells(&ab.path), vec![(0, 1), (1, 0)]);
+        assert_eq!(ab.other_paths.len(), 1);
+        assert_eq!(cells(&ab.other_paths[0]), vec![(0, 0), (1, 0)]);
+        assert_eq!(solution.score(), 5);
+    }
+
+    #[test]
+    fn test_scorer() {
+        let mut grid = grid::Grid::init(3, 2);
+        grid.set('c', 4, 0, 0);
+        grid.set('a', 1, 0, 1);
+        grid.set('x', 9, 0, 2);
+        grid.set('o', 1, 1, 0);
+        grid.set('t', 2, 1, 1);
+        grid.set('s', 2, 1, 2);
+        let words = vec![String::from("cat"), String::from("coats")];
+
+        let dictionary = dictionary::GraphDictionary::new(&words).unwrap();
+        let solution = Solver::new(dictionary, grid.clone()).unwrap().solve_grid();
+        assert_eq!(solution.get("coats").unwrap().score, 15);
+        assert_eq!(solution.score(), 7 + 15);
+
+        let dictionary = dictionary::GraphDictionary::new(&words).unwrap();
+        let solution = Solver::with_scorer(dictionary, grid, Box::new(scoring::BoggleScorer))
+            .unwrap()
+            .solve_grid();
+        assert_eq!(solution.get("coats").unwrap().score, 2);
+        assert_eq!(solution.score(), 3);
+
+        // Huge tile values don't overflow the score
+        let solution = solve("a:4294967295 t:1", &["at"]);
+        assert_eq!(solution.score(), u32::MAX);
+    }
+
+    #[test]
+    fn test_dictionaries_solve_alike() {
+        let mut grid = grid::Grid::init(3, 2);
+        grid.set('c', 4, 0, 0);
+        grid.set('a', 1, 0, 1);
+        grid.set('x', 9, 0, 2);
+        grid.set('o', 1, 1, 0);
+        grid.set('t', 2, 1, 1);
+        grid.set('s', 2, 1, 2);
+        let words = word_list(&[
+            "a", "at", "cat", "cats", "coat", "coats", "taco", "tax", "dog",
+        ]);
+
+        let graph = Solver::new(
+            dictionary::GraphDictionary::new(&words).unwrap(),
+            grid.clone(),
+        )
+        .unwrap()
+        .solve_grid();
+        let hash_map = Solver::new(dictionary::HashMapDictionary::new(&words), grid)
+            .unwrap()
+            .solve_grid();
+        assert_eq!(graph.words(), hash_map.words());
+        assert_eq!(graph.words().len(), 8);
+    }
+
+    #[test]
+    fn test_borrowed_dictionary() {
+        let words = vec![String::from("cat"), String::from("act")];
+        let dictionary = dictionary::GraphDictionary::new(&words).unwrap();
+        for (board, expected) in [("c a\nx t", 2), ("a c\nx t", 2), ("c x\nx t", 0)].iter() {
+            let grid: grid::Grid = board.parse().unwrap();
+            let solution = Solver::new(&dictionary, grid).unwrap().solve_grid();
+            assert_eq!(solution.words().len(), *expected, "{}", board);
+        }
+    }
+
+    #[test]
+    fn test_multi_letter_tiles() {
+        let solution = solve("qu:8 i:2\nt:2 e:1", &["quit", "quite", "tie", "qit", "ut"]);
+
+        assert_eq!(solution.words().len(), 3);
+        let quite = solution.get("quite").unwrap();
+        assert_eq!(cells(&quite.path), vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+        // Five letters earns the x1.5 bonus, even though only four tiles were used
+        assert_eq!(quite.score, 19);
+        assert_eq!(solution.get("quit").unwrap().path.len(), 3);
+        assert!(solution.get("tie").is_some());
+    }
+
+    #[test]
+    fn test_placement_tiles() {
+        let solution = solve(
+            "re-:5 d:3\n-ing:7 o:2",
+            &["redo", "redoing", "doing", "dore", "ingo", "doings"],
+        );
+
+        let found: Vec<&str> = solution.words().iter().map(|w| w.word.as_str()).collect();
+        // "dore" would use "re-" at the end, and "ingo" would use "-ing" at the start
+        assert_eq!(found, vec!["redo", "redoing", "doing"]);
+        assert_eq!(solution.get("redoing").unwrap().path.len(), 4);
+    }
+
+    #[test]
+    fn test_either_or_tiles() {
+        let solution = solve("b:5 a/e:1\nd:3 x:9", &["bad", "bed", "bid", "dab"]);
+
+        let found: Vec<&str> = solution.words().iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(found, vec!["bad", "bed", "dab"]);
+        assert_eq!(solution.get("bad").unwrap().path[1], PathStep::new(0, 1, 0));
+        assert_eq!(solution.get("bed").unwrap().path[1], PathStep::new(0, 1, 1));
+    }
+
+    #[test]
+    fn test_word_lengths() {
+        let words = [
+            "a", "at", "cat", "cats", "coat", "coats", "taco", "tax", "quit", "quits",
+        ];
+        let found = |config: SolverConfig| {
+            let solution = solve_with("c a x\no t s\nqu i t", &words, config);
+            let mut words: Vec<String> = solution.words().iter().map(|w| w.word.clone()).collect();
+            words.sort();
+            words
+        };
+
+        assert_eq!(found(SolverConfig::default()).len(), 10);
+        assert_eq!(
+            found(SolverConfig::wordament()),
+            vec!["cat", "cats", "coat", "coats", "quit", "quits", "taco", "tax"]
+        );
+        // "qu" is two letters, so "quit" is four letters long even though it only uses three tiles
+        let config = SolverConfig {
+            min_word_length: 2,
+            max_word_length: Some(4),
+            keep_other_paths: false,
+        };
+        assert_eq!(
+            found(config),
+            vec!["at", "cat", "cats", "coat", "quit", "taco", "tax"]
+        );
+
+        // The first tile can already be too long on its own
+        let config = SolverConfig {
+            min_word_length: 1,
+            max_word_length: Some(2),
+            keep_other_paths: false,
+        };
+        let solution = solve_with("ing x\nqu i", &["ing", "qui", "x", "xi"], config);
+        let found: Vec<&str> = solution.words().iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(found, vec!["x", "xi"]);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel() {
+        let grid: grid::Grid = "c a x\no t s\nqu/e -ing re-".parse().unwrap();
+        let words = word_list(&[
+            "at", "cat", "cats", "coat", "coats", "taco", "tax", "toting", "sting", "tea",
+        ]);
+
+        let sequential = Solver::new(
+            dictionary::GraphDictionary::new(&words).unwrap(),
+            grid.clone(),
+        )
+        .unwrap()
+        .solve_grid();
+        let parallel = Solver::new(dictionary::GraphDictionary::new(&words).unwrap(), grid)
+            .unwrap()
+            .solve_grid_parallel();
+        assert_eq!(sequential.words(), parallel.words());
+        assert_eq!(sequential.score(), parallel.score());
+        assert!(parallel.get("sting").is_some());
+    }
+
+    #[test]
+    fn test_bad_grids() {
+        let dictionary = || dictionary::GraphDictionary::new(&[String::from("ab")]).unwrap();
+        assert!(matches!(
+            Solver::new(dictionary(), grid::Grid::init(0, 0)),
+            Err(Error::EmptyGrid)
+        ));
+        let mut grid = grid::Grid::init(2, 1);
+        grid.set('a', 1, 0, 0);
+        assert!(matches!(
+            Solver::new(dictionary(), grid),
+            Err(Error::BlankTile { row: 0, col: 1 })
+        ));
     }
 }
@@ -1,13 +1,68 @@
-use wordament_solver::dictionary::GraphDictionary;
-use wordament_solver::grid;
-use wordament_solver::Solver;
+use wordament_solver::alphabet::Alphabet;
+use wordament_solver::dictionary::{
+    self, DawgDictionary, Dictionary, GraphDictionary, HashMapDictionary, InvalidWords,
+    NormalizeOptions,
+};
+use wordament_solver::generator::{self, Constraints, Generator, LetterSource};
+use wordament_solver::grid::Grid;
+use wordament_solver::output::{self, Delimiter};
+use wordament_solver::render;
+use wordament_solver::scoring::{BoggleScorer, Scorer, WordamentScorer};
+use wordament_solver::{Error, FoundWord, Solution, Solver, SolverConfig, SortOrder};
 
 use std::{
-    fs::File,
-    io::{prelude::*, BufReader},
-    path::Path,
+    env, fs,
+    io::{self, prelude::*},
+    path::PathBuf,
+    process,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
+const USAGE: &str = "\
+Usage: wordament_solver [OPTIONS] (--grid <GRID> | --grid-file <PATH> | --interactive)
+       wordament_solver compile [--alphabet <NAME>] [--strict] <WORD LIST> <OUTPUT>
+       wordament_solver generate [--dice <DICE>] [--size <WIDTHxHEIGHT>] [--seed <N>] [--count <N>]
+                                 [--min-words <N>] [--min-longest <N>] [--target-score <N>] [--attempts <N>]
+                                 [--dictionary <PATH>] [--dictionary-kind <KIND>] [--alphabet <NAME>] [--strict]
+
+Finds every word in a Wordament or Boggle grid. The compile command turns a word list into a compiled dictionary, which
+loads much faster and can be given to --dictionary in place of the word list. The generate command prints random
+boards for practice, in the same format --grid-file reads; --dice picks classic (4x4 Boggle), big (5x5 Big Boggle) or
+english (letters drawn by how common they are in English, on a board of any --size) [default: classic]. The seed is
+printed so a board can be made again; by default it comes from the clock. Boards can be required to have at least
+--min-words words, a word of at least --min-longest letters, or a Wordament score of at least --target-score, using the
+dictionary options below; up to --attempts boards are tried for each one [default: 1000].
+
+Options:
+  -g, --grid <GRID>         The grid to solve, with rows separated by ';' (e.g. \"a b c; d e f; g h i\")
+  -f, --grid-file <PATH>    Read the grid to solve from a file, one row per line
+  -i, --interactive         Load the dictionary once, then keep asking for 4x4 boards to solve until told to quit
+  -d, --dictionary <PATH>   The word list to use, one word per line, or a compiled dictionary [default: words_alpha.txt]
+  -k, --dictionary-kind <KIND>
+                            How to store the word list: trie, dawg or hashmap (compiled dictionaries are always a
+                            dawg) [default: trie]
+  -a, --alphabet <NAME>     The letters words can use: english, french, spanish or german [default: english, or
+                            the alphabet a compiled dictionary was made with]
+      --strict              Fail if the word list contains a word with letters outside the alphabet, instead of skipping it
+  -m, --min-length <N>      Only report words with at least this many letters [default: 3]
+  -M, --max-length <N>      Only report words with at most this many letters [default: no limit]
+      --all-paths           Keep every path to each word, not just the best one (listed in json output)
+  -s, --scoring <MODEL>     How to score words: wordament or boggle [default: wordament]
+  -o, --format <FORMAT>     How to print the solution: text, words, csv, tsv or json (json needs the serde feature)
+                            [default: text]
+      --sort <ORDER>        The order to list words in: found, alpha, length, score or start [default: found]
+      --group-by <GROUP>    List words under a heading for each start tile or length: start or length (text format
+                            only)
+  -r, --render <WORD>       Draw the path of a word on the grid (in text output, or in the --svg image)
+      --render-all          Draw the path of every word on the grid (in text output, or in the --svg image)
+      --svg <PATH>          Save an SVG image of the grid with the paths of the words picked by --render or
+                            --render-all (every word if neither is given)
+  -h, --help                Print this message
+
+Each tile is one or more letters, optionally followed by its value (e.g. a:2 or qu:9). Tiles that can only start or
+end a word are written re- and -ing, and either/or tiles are written a/e.
+";
+
 /** Assumptions:
  * - Words must be made by connecting letters adjacent to each other, along a path.
  * - Letters cannot be reused.
@@ -17,31 +72,1143 @@ use std::{
  * |  c  | (d) |
  */
 fn main() {
-    let mut grid = grid::Grid::init(2, 2);
-    grid.set('a', 1, 0, 0);
-    grid.set('b', 1, 0, 1);
-    grid.set('c', 1, 1, 0);
-    grid.set('d', 1, 1, 1);
+    let mut args = env::args().skip(1).peekable();
+    let command = if args.peek().map(String::as_str) == Some("compile") {
+        args.next();
+        CompileOptions::parse(args).map(|options| options.map(Command::Compile))
+    } else if args.peek().map(String::as_str) == Some("generate") {
+        args.next();
+        GenerateOptions::parse(args).map(|options| options.map(Command::Generate))
+    } else {
+        Options::parse(args).map(|options| options.map(Command::Solve))
+    };
+    let command = match command {
+        Ok(Some(command)) => command,
+        // Help was asked for
+        Ok(None) => {
+            print!("{}", USAGE);
+            return;
+        }
+        Err(why) => {
+            eprintln!("error: {}\n\n{}", why, USAGE);
+            process::exit(2);
+        }
+    };
+    let result = match &command {
+        Command::Solve(options) => run(options),
+        Command::Compile(options) => compile(options),
+        Command::Generate(options) => generate(options),
+    };
+    if let Err(why) = result {
+        eprintln!("error: {}", why);
+        process::exit(1);
+    }
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let grid: Option<Grid> = match &options.grid {
+        Some(GridSource::Inline(grid)) => {
+            Some(grid.replace(';', "\n").parse().map_err(Error::from))
+        }
+        Some(GridSource::File(path)) => Some(Grid::from_file(path)),
+        None => None,
+    }
+    .transpose()
+    .map_err(|why| format!("couldn't read grid: {}", why))?;
+
+    let (dictionary, alphabet) =
+        load_dictionary(&options.dictionary, options.format == Format::Text)?;
+    match grid {
+        Some(grid) => {
+            let solution = dictionary.solve(options, &alphabet, &grid)?;
+            finish(&mut io::stdout().lock(), options, &grid, &solution)
+        }
+        None => interactive(
+            &mut io::stdin().lock(),
+            &mut io::stdout().lock(),
+            options,
+            &dictionary,
+            &alphabet,
+        )
+        .map_err(|why| format!("couldn't read the board: {}", why)),
+    }
+}
+
+/** A dictionary of whichever kind was asked for, loaded once so that it can solve any number of boards.
+ */
+enum LoadedDictionary {
+    Trie(GraphDictionary),
+    Dawg(DawgDictionary),
+    HashMap(HashMapDictionary),
+}
+impl LoadedDictionary {
+    fn solve(
+        &self,
+        options: &Options,
+        alphabet: &Alphabet,
+        grid: &Grid,
+    ) -> Result<Solution, String> {
+        grid.check_alphabet(alphabet)
+            .map_err(|why| format!("can't solve this grid: {}", why))?;
+        match self {
+            LoadedDictionary::Trie(dictionary) => solve(dictionary, options, grid),
+            LoadedDictionary::Dawg(dictionary) => solve(dictionary, options, grid),
+            LoadedDictionary::HashMap(dictionary) => solve(dictionary, options, grid),
+        }
+    }
+
+    fn matching_boards(
+        &self,
+        generator: &mut Generator,
+        options: &GenerateOptions,
+    ) -> Result<Vec<Grid>, String> {
+        match self {
+            LoadedDictionary::Trie(dictionary) => matching_boards(generator, dictionary, options),
+            LoadedDictionary::Dawg(dictionary) => matching_boards(generator, dictionary, options),
+            LoadedDictionary::HashMap(dictionary) => {
+                matching_boards(generator, dictionary, options)
+            }
+        }
+    }
+}
+
+/* Loads the dictionary, returning it along with the alphabet its words are made of. What was loaded is reported on
+ * stderr if `report` is set.
+ */
+fn load_dictionary(
+    options: &DictionaryOptions,
+    report: bool,
+) -> Result<(LoadedDictionary, Alphabet), String> {
+    let couldnt_load = |why| format!("couldn't load {}: {}", options.path.display(), why);
+    let bytes = fs::read(&options.path).map_err(|why| couldnt_load(why.into()))?;
+    if DawgDictionary::is_compiled(&bytes) {
+        let dictionary = DawgDictionary::from_bytes(&bytes).map_err(couldnt_load)?;
+        // A compiled dictionary can only be searched with the alphabet it was made with
+        if let Some(alphabet) = options.alphabet.as_ref() {
+            if alphabet != dictionary.alphabet() {
+                return Err(format!(
+                    "{} was compiled for the {} alphabet, not {}",
+                    options.path.display(),
+                    dictionary.alphabet().name(),
+                    alphabet.name()
+                ));
+            }
+        }
+        if report {
+            eprintln!(
+                "{}: compiled {} dictionary",
+                options.path.display(),
+                dictionary.alphabet().name()
+            );
+        }
+        let alphabet = dictionary.alphabet().clone();
+        return Ok((LoadedDictionary::Dawg(dictionary), alphabet));
+    }
+
+    let alphabet = options.alphabet.clone().unwrap_or_else(Alphabet::english);
+    let lines = String::from_utf8_lossy(&bytes);
+    let (words, normalized) =
+        dictionary::normalize(lines.lines(), &normalize_options(&alphabet, options.strict))
+            .map_err(couldnt_load)?;
+    if report {
+        eprintln!("{}: {}", options.path.display(), normalized);
+    }
+
+    let dictionary = match options.kind {
+        DictionaryKind::Trie => LoadedDictionary::Trie(
+            GraphDictionary::with_alphabet(&words, alphabet.clone()).map_err(couldnt_load)?,
+        ),
+        DictionaryKind::Dawg => LoadedDictionary::Dawg(
+            DawgDictionary::with_alphabet(&words, alphabet.clone()).map_err(couldnt_load)?,
+        ),
+        DictionaryKind::HashMap => LoadedDictionary::HashMap(HashMapDictionary::new(&words)),
+    };
+    Ok((dictionary, alphabet))
+}
+
+/* Read boards from `input` and solve them one after another, until the input runs out or the user quits. Problems
+ * with a board are reported and then the next board is asked for.
+ */
+fn interactive<R: BufRead, W: Write>(
+    input: &mut R,
+    out: &mut W,
+    options: &Options,
+    dictionary: &LoadedDictionary,
+    alphabet: &Alphabet,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "Enter the {} tiles of each board, row by row, on one line or several (e.g. \"a b qu:9 re- ...\"). A board of \
+         single letters can be typed without spaces. Enter \"quit\" to stop.",
+        BOARD_SIZE * BOARD_SIZE
+    )?;
+    loop {
+        let grid = match read_board(input, out)? {
+            None => return Ok(()),
+            Some(Ok(grid)) => grid,
+            Some(Err(why)) => {
+                writeln!(out, "error: {}", why)?;
+                continue;
+            }
+        };
+        let result = dictionary
+            .solve(options, alphabet, &grid)
+            .and_then(|solution| finish(out, options, &grid, &solution));
+        if let Err(why) = result {
+            writeln!(out, "error: {}", why)?;
+        }
+    }
+}
 
-    println!("Grid:\n{:?}", grid);
+// The width and height of the boards entered in interactive mode
+const BOARD_SIZE: usize = 4;
 
-    // Set up the dictionary
+/* Prompt for tiles until there are enough for a board. Returns None at the end of the input, or if the user quits
+ * instead of starting a board.
+ */
+fn read_board<R: BufRead, W: Write>(
+    input: &mut R,
+    out: &mut W,
+) -> io::Result<Option<Result<Grid, String>>> {
+    let tile_count = BOARD_SIZE * BOARD_SIZE;
+    let mut tiles: Vec<String> = Vec::new();
+    while tiles.len() < tile_count {
+        if tiles.is_empty() {
+            write!(out, "\nboard> ")?;
+        } else {
+            write!(out, "({} more)> ", tile_count - tiles.len())?;
+        }
+        out.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            writeln!(out)?;
+            return Ok(None);
+        }
+        let line = line.trim();
+        if tiles.is_empty() && matches!(line, "quit" | "exit") {
+            return Ok(None);
+        }
+        // A whole board of single letters, typed without spaces
+        if tiles.is_empty()
+            && line.chars().count() == tile_count
+            && line.chars().all(char::is_alphabetic)
+        {
+            tiles.extend(line.chars().map(String::from));
+            continue;
+        }
+        tiles.extend(
+            line.split(|c: char| c.is_whitespace() || c == '|')
+                .filter(|tile| !tile.is_empty())
+                .map(String::from),
+        );
+    }
+    if tiles.len() > tile_count {
+        return Ok(Some(Err(format!(
+            "a board has {} tiles, but {} were entered",
+            tile_count,
+            tiles.len()
+        ))));
+    }
+    let rows: Vec<String> = tiles.chunks(BOARD_SIZE).map(|row| row.join(" ")).collect();
+    Ok(Some(
+        rows.join("\n")
+            .parse::<Grid>()
+            .map_err(|why| why.to_string()),
+    ))
+}
 
-    let path = Path::new("words_alpha.txt");
-    let file = match File::open(&path) {
-        Err(why) => panic!("couldn't open {}: {}", path.display(), why),
-        Ok(file) => file,
+// Print the solution, and save it as an image if that was asked for
+fn finish<W: Write>(
+    out: &mut W,
+    options: &Options,
+    grid: &Grid,
+    solution: &Solution,
+) -> Result<(), String> {
+    print_solution(out, options, grid, solution)
+        .map_err(|why| format!("couldn't write the solution: {}", why))?;
+    if let Some(path) = &options.svg {
+        let words: Vec<&FoundWord> = match &options.render {
+            None | Some(Render::All) => solution.sorted(options.sort),
+            Some(Render::Word(word)) => solution.get(word).into_iter().collect(),
+        };
+        fs::write(path, render::render_svg(grid, &words))
+            .map_err(|why| format!("couldn't write {}: {}", path.display(), why))?;
+    }
+    Ok(())
+}
+
+// Turns a word list into a compiled dictionary
+fn compile(options: &CompileOptions) -> Result<(), String> {
+    let (dictionary, report) = DawgDictionary::load(
+        &options.words,
+        &normalize_options(&options.alphabet, options.strict),
+    )
+    .map_err(|why| format!("couldn't load {}: {}", options.words.display(), why))?;
+    eprintln!("{}: {}", options.words.display(), report);
+    let bytes = dictionary.to_bytes();
+    fs::write(&options.output, &bytes)
+        .map_err(|why| format!("couldn't write {}: {}", options.output.display(), why))?;
+    eprintln!(
+        "{}: {} nodes, {} bytes",
+        options.output.display(),
+        dictionary.node_count(),
+        bytes.len()
+    );
+    Ok(())
+}
+
+// Prints random boards
+fn generate(options: &GenerateOptions) -> Result<(), String> {
+    let seed = options.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+    });
+    eprintln!("seed: {}", seed);
+    let mut generator = Generator::new(options.source, seed);
+    let boards = if options.constraints.is_empty() {
+        (0..options.count).map(|_| generator.generate()).collect()
+    } else {
+        let (dictionary, alphabet) = load_dictionary(&options.dictionary, true)?;
+        // Generated boards are made of the letters a-z, so the dictionary has to be able to spell with them
+        if let Some(letter) = ('a'..='z').find(|&letter| !alphabet.contains(letter)) {
+            return Err(format!(
+                "generated boards use the letters a-z, but the {} alphabet doesn't have '{}'",
+                alphabet.name(),
+                letter
+            ));
+        }
+        dictionary.matching_boards(&mut generator, options)?
     };
-    let reader = BufReader::new(&file);
-
-    let words: Vec<String> = reader
-        .lines()
-        .filter(|x| x.is_ok())
-        .map(|x| x.unwrap())
-        .collect();
-    let dbd = GraphDictionary::new(&words);
-    println!("Instantiated dictionary");
-
-    let solver = Solver::<GraphDictionary>::new(dbd, grid);
-    println!("Solution: {}", solver.solve_grid())
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for (i, board) in boards.iter().enumerate() {
+        if i > 0 {
+            writeln!(out).map_err(|why| why.to_string())?;
+        }
+        write!(out, "{}", board).map_err(|why| why.to_string())?;
+    }
+    Ok(())
+}
+
+// Generates boards that meet the constraints, reporting what each one has to offer
+fn matching_boards<D: Dictionary>(
+    generator: &mut Generator,
+    dictionary: &D,
+    options: &GenerateOptions,
+) -> Result<Vec<Grid>, String> {
+    let mut boards = Vec::new();
+    for _ in 0..options.count {
+        let board = generator
+            .generate_matching(
+                dictionary,
+                WordamentScorer,
+                SolverConfig::wordament(),
+                &options.constraints,
+                options.attempts,
+            )
+            .map_err(|why| why.to_string())?;
+        eprintln!(
+            "{} words, longest {} letters, score {} (tried {} boards)",
+            board.solution.words().len(),
+            generator::longest_word(&board.solution),
+            board.solution.score(),
+            board.boards_tried
+        );
+        boards.push(board.grid);
+    }
+    Ok(boards)
+}
+
+fn normalize_options(alphabet: &Alphabet, strict: bool) -> NormalizeOptions {
+    NormalizeOptions {
+        alphabet: alphabet.clone(),
+        invalid_words: if strict {
+            InvalidWords::Reject
+        } else {
+            InvalidWords::Skip
+        },
+        ..NormalizeOptions::default()
+    }
+}
+
+// Every dictionary kind is Sync, so they can all be shared between threads when solving in parallel
+fn solve<D: Dictionary + Sync>(
+    dictionary: D,
+    options: &Options,
+    grid: &Grid,
+) -> Result<Solution, String> {
+    let solver = Solver::with_config(
+        dictionary,
+        grid.clone(),
+        options.scoring.scorer(),
+        options.config,
+    )
+    .map_err(|why| format!("can't solve this grid: {}", why))?;
+    #[cfg(feature = "parallel")]
+    let solution = solver.solve_grid_parallel();
+    #[cfg(not(feature = "parallel"))]
+    let solution = solver.solve_grid();
+    Ok(solution)
+}
+
+fn print_solution<W: Write>(
+    out: &mut W,
+    options: &Options,
+    grid: &Grid,
+    solution: &Solution,
+) -> io::Result<()> {
+    let words = solution.sorted(options.sort);
+    match options.format {
+        Format::Text => {
+            writeln!(out, "Grid:\n{:?}", grid)?;
+            writeln!(out, "Score: {} ({} words)", solution.score(), words.len())?;
+            let width = words
+                .iter()
+                .map(|w| w.word.chars().count())
+                .max()
+                .unwrap_or(0);
+            let groups: Vec<(String, Vec<&FoundWord>)> = match options.group_by {
+                None => vec![(String::new(), words)],
+                Some(Grouping::Start) => solution
+                    .grouped_by_start(options.sort)
+                    .into_iter()
+                    .map(|(start, words)| match start {
+                        Some((row, col)) => {
+                            let tile = grid.get(row, col);
+                            (format!("{:?} at ({},{}):", tile, row, col), words)
+                        }
+                        None => (String::from("No path:"), words),
+                    })
+                    .collect(),
+                Some(Grouping::Length) => solution
+                    .grouped_by_length(options.sort)
+                    .into_iter()
+                    .map(|(length, words)| (format!("{} letters:", length), words))
+                    .collect(),
+            };
+            for (heading, words) in groups {
+                if !heading.is_empty() {
+                    writeln!(out, "{}", heading)?;
+                }
+                for found in words {
+                    writeln!(
+                        out,
+                        "  {:<width$}  {:>4}  {}",
+                        found.word,
+                        found.score,
+                        output::format_path(grid, found),
+                        width = width
+                    )?;
+                }
+            }
+            let rendered: Vec<&FoundWord> = match &options.render {
+                None => Vec::new(),
+                Some(Render::All) => solution.sorted(options.sort),
+                Some(Render::Word(word)) => match solution.get(word) {
+                    Some(found) => vec![found],
+                    None => {
+                        writeln!(out, "\n{} isn't one of the words found", word)?;
+                        Vec::new()
+                    }
+                },
+            };
+            for found in rendered {
+                write!(out, "\n{}", render::render_word(grid, found))?;
+            }
+        }
+        Format::Words => {
+            for found in words {
+                writeln!(out, "{}", found.word)?;
+            }
+        }
+        Format::Csv => output::write_delimited(out, grid, &words, Delimiter::Comma)?,
+        Format::Tsv => output::write_delimited(out, grid, &words, Delimiter::Tab)?,
+        #[cfg(feature = "serde")]
+        Format::Json => output::write_json(out, grid, solution, &words)?,
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Solve(Options),
+    Compile(CompileOptions),
+    Generate(GenerateOptions),
+}
+
+#[derive(Debug, PartialEq)]
+enum GridSource {
+    Inline(String),
+    File(PathBuf),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DictionaryKind {
+    Trie,
+    Dawg,
+    HashMap,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Scoring {
+    Wordament,
+    Boggle,
+}
+impl Scoring {
+    fn scorer(self) -> Box<dyn Scorer> {
+        match self {
+            Scoring::Wordament => Box::new(WordamentScorer),
+            Scoring::Boggle => Box::new(BoggleScorer),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Text,
+    Words,
+    Csv,
+    Tsv,
+    #[cfg(feature = "serde")]
+    Json,
+}
+
+#[derive(Debug, PartialEq)]
+enum Render {
+    All,
+    Word(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Grouping {
+    Start,
+    Length,
+}
+
+/** Where to find the dictionary, and how to build it from a word list.
+ */
+#[derive(Debug, PartialEq)]
+struct DictionaryOptions {
+    path: PathBuf,
+    kind: DictionaryKind,
+    // None means English for a word list, or whatever alphabet a compiled dictionary was made with
+    alphabet: Option<Alphabet>,
+    strict: bool,
+}
+impl DictionaryOptions {
+    /** Apply one of the dictionary flags, taking its value from `value` if it needs one. Returns false if the flag
+     * isn't a dictionary flag.
+     */
+    fn parse_flag<F>(&mut self, flag: &str, value: F) -> Result<bool, String>
+    where
+        F: FnOnce() -> Result<String, String>,
+    {
+        match flag {
+            "-d" | "--dictionary" => self.path = PathBuf::from(value()?),
+            "-k" | "--dictionary-kind" => {
+                self.kind = match value()?.as_str() {
+                    "trie" => DictionaryKind::Trie,
+                    "dawg" => DictionaryKind::Dawg,
+                    "hashmap" => DictionaryKind::HashMap,
+                    other => return Err(format!("unknown dictionary kind '{}'", other)),
+                }
+            }
+            "-a" | "--alphabet" => {
+                let name = value()?;
+                self.alphabet = Some(
+                    Alphabet::by_name(&name)
+                        .ok_or_else(|| format!("unknown alphabet '{}'", name))?,
+                );
+            }
+            "--strict" => self.strict = true,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+impl Default for DictionaryOptions {
+    fn default() -> DictionaryOptions {
+        DictionaryOptions {
+            path: PathBuf::from("words_alpha.txt"),
+            kind: DictionaryKind::Trie,
+            alphabet: None,
+            strict: false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    // None in interactive mode
+    grid: Option<GridSource>,
+    dictionary: DictionaryOptions,
+    config: SolverConfig,
+    scoring: Scoring,
+    format: Format,
+    sort: SortOrder,
+    group_by: Option<Grouping>,
+    render: Option<Render>,
+    svg: Option<PathBuf>,
+}
+impl Options {
+    /** Parse the command line arguments (not including the program name). Returns None if help was asked for.
+     */
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Option<Options>, String> {
+        let mut interactive = false;
+        let mut options = Options {
+            grid: None,
+            dictionary: DictionaryOptions::default(),
+            config: SolverConfig::wordament(),
+            scoring: Scoring::Wordament,
+            format: Format::Text,
+            sort: SortOrder::Found,
+            group_by: None,
+            render: None,
+            svg: None,
+        };
+
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = split_flag(&arg);
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} needs a value", flag))
+            };
+            if options.dictionary.parse_flag(flag, &mut value)? {
+                continue;
+            }
+            match flag {
+                "-h" | "--help" => return Ok(None),
+                "-g" | "--grid" => options.grid = Some(GridSource::Inline(value()?)),
+                "-f" | "--grid-file" => {
+                    options.grid = Some(GridSource::File(PathBuf::from(value()?)))
+                }
+                "-i" | "--interactive" => interactive = true,
+                "-m" | "--min-length" => {
+                    let v = value()?;
+                    options.config.min_word_length = v
+                        .parse()
+                        .map_err(|_| format!("invalid minimum length '{}'", v))?;
+                }
+                "-M" | "--max-length" => {
+                    let v = value()?;
+                    options.config.max_word_length = Some(
+                        v.parse()
+                            .map_err(|_| format!("invalid maximum length '{}'", v))?,
+                    );
+                }
+                "--all-paths" => options.config.keep_other_paths = true,
+                "-s" | "--scoring" => {
+                    options.scoring = match value()?.as_str() {
+                        "wordament" => Scoring::Wordament,
+                        "boggle" => Scoring::Boggle,
+                        other => return Err(format!("unknown scoring model '{}'", other)),
+                    }
+                }
+                "-o" | "--format" => {
+                    options.format = match value()?.as_str() {
+                        "text" => Format::Text,
+                        "words" => Format::Words,
+                        "csv" => Format::Csv,
+                        "tsv" => Format::Tsv,
+                        #[cfg(feature = "serde")]
+                        "json" => Format::Json,
+                        #[cfg(not(feature = "serde"))]
+                        "json" => return Err(String::from(
+                            "json output needs the serde feature (cargo build --features serde)",
+                        )),
+                        other => return Err(format!("unknown output format '{}'", other)),
+                    }
+                }
+                "--sort" => {
+                    options.sort = match value()?.as_str() {
+                        "found" => SortOrder::Found,
+                        "alpha" => SortOrder::Alphabetical,
+                        "length" => SortOrder::Length,
+                        "score" => SortOrder::Score,
+                        "start" => SortOrder::StartCell,
+                        other => return Err(format!("unknown sort order '{}'", other)),
+                    }
+                }
+                "--group-by" => {
+                    options.group_by = match value()?.as_str() {
+                        "start" => Some(Grouping::Start),
+                        "length" => Some(Grouping::Length),
+                        other => return Err(format!("unknown grouping '{}'", other)),
+                    }
+                }
+                "-r" | "--render" => options.render = Some(Render::Word(value()?.to_lowercase())),
+                "--render-all" => options.render = Some(Render::All),
+                "--svg" => options.svg = Some(PathBuf::from(value()?)),
+                other => return Err(format!("unexpected argument '{}'", other)),
+            }
+        }
+
+        if options.group_by.is_some() && options.format != Format::Text {
+            return Err(String::from("--group-by only works with --format text"));
+        }
+        if options.render.is_some() && options.svg.is_none() && options.format != Format::Text {
+            return Err(String::from(
+                "--render and --render-all only work with --format text, unless --svg is given",
+            ));
+        }
+        match (&options.grid, interactive) {
+            (None, false) => {
+                return Err(String::from(
+                    "no grid given; use --grid, --grid-file or --interactive",
+                ))
+            }
+            (Some(_), true) => {
+                return Err(String::from(
+                    "--interactive asks for boards, so it can't be used with --grid or --grid-file",
+                ))
+            }
+            _ => {}
+        }
+        Ok(Some(options))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct CompileOptions {
+    words: PathBuf,
+    output: PathBuf,
+    alphabet: Alphabet,
+    strict: bool,
+}
+impl CompileOptions {
+    /** Parse the arguments that come after "compile". Returns None if help was asked for.
+     */
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Option<CompileOptions>, String> {
+        let mut alphabet = Alphabet::english();
+        let mut strict = false;
+        let mut paths: Vec<PathBuf> = Vec::new();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = split_flag(&arg);
+            match flag {
+                "-h" | "--help" => return Ok(None),
+                "-a" | "--alphabet" => {
+                    let name = inline_value
+                        .or_else(|| args.next())
+                        .ok_or_else(|| format!("{} needs a value", flag))?;
+                    alphabet = Alphabet::by_name(&name)
+                        .ok_or_else(|| format!("unknown alphabet '{}'", name))?;
+                }
+                "--strict" => strict = true,
+                other if other.starts_with('-') => {
+                    return Err(format!("unexpected argument '{}'", other))
+                }
+                _ => paths.push(PathBuf::from(arg)),
+            }
+        }
+        let mut paths = paths.into_iter();
+        match (paths.next(), paths.next(), paths.next()) {
+            (Some(words), Some(output), None) => Ok(Some(CompileOptions {
+                words,
+                output,
+                alphabet,
+                strict,
+            })),
+            _ => Err(String::from(
+                "compile needs a word list and a path to write the compiled dictionary to",
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct GenerateOptions {
+    source: LetterSource,
+    // None means the seed comes from the clock
+    seed: Option<u64>,
+    count: usize,
+    constraints: Constraints,
+    // How many boards to try for each one printed
+    attempts: usize,
+    // Only loaded if there are constraints to check
+    dictionary: DictionaryOptions,
+}
+impl GenerateOptions {
+    /** Parse the arguments that come after "generate". Returns None if help was asked for.
+     */
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Option<GenerateOptions>, String> {
+        let mut dice = String::from("classic");
+        let mut size: Option<(usize, usize)> = None;
+        let mut seed = None;
+        let mut count = 1;
+        let mut constraints = Constraints::default();
+        let mut attempts = 1000;
+        let mut dictionary = DictionaryOptions::default();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = split_flag(&arg);
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} needs a value", flag))
+            };
+            if dictionary.parse_flag(flag, &mut value)? {
+                continue;
+            }
+            match flag {
+                "-h" | "--help" => return Ok(None),
+                "--dice" => dice = value()?,
+                "--size" => {
+                    let v = value()?;
+                    size = match v.split_once('x') {
+                        Some((width, height)) => match (width.parse(), height.parse()) {
+                            (Ok(width), Ok(height)) if width > 0 && height > 0 => {
+                                Some((width, height))
+                            }
+                            _ => None,
+                        },
+                        None => None,
+                    };
+                    if size.is_none() {
+                        return Err(format!("invalid board size '{}'", v));
+                    }
+                }
+                "--seed" => {
+                    let v = value()?;
+                    seed = Some(v.parse().map_err(|_| format!("invalid seed '{}'", v))?);
+                }
+                "-n" | "--count" => {
+                    let v = value()?;
+                    count = v.parse().map_err(|_| format!("invalid count '{}'", v))?;
+                }
+                "--min-words" => {
+                    let v = value()?;
+                    constraints.min_words = Some(
+                        v.parse()
+                            .map_err(|_| format!("invalid number of words '{}'", v))?,
+                    );
+                }
+                "--min-longest" => {
+                    let v = value()?;
+                    constraints.min_longest_word = Some(
+                        v.parse()
+                            .map_err(|_| format!("invalid word length '{}'", v))?,
+                    );
+                }
+                "--target-score" => {
+                    let v = value()?;
+                    constraints.target_score =
+                        Some(v.parse().map_err(|_| format!("invalid score '{}'", v))?);
+                }
+                "--attempts" => {
+                    let v = value()?;
+                    attempts = match v.parse() {
+                        Ok(attempts) if attempts > 0 => attempts,
+                        _ => return Err(format!("invalid number of attempts '{}'", v)),
+                    };
+                }
+                other => return Err(format!("unexpected argument '{}'", other)),
+            }
+        }
+        let source = match (dice.as_str(), size) {
+            ("classic", None) | ("classic", Some((4, 4))) => LetterSource::ClassicDice,
+            ("big", None) | ("big", Some((5, 5))) => LetterSource::BigBoggleDice,
+            ("classic", _) | ("big", _) => {
+                return Err(format!(
+                    "the {} dice only make one size of board; use --dice english for other sizes",
+                    dice
+                ))
+            }
+            ("english", size) => {
+                let (width, height) = size.unwrap_or((4, 4));
+                LetterSource::EnglishFrequencies { width, height }
+            }
+            (other, _) => return Err(format!("unknown dice '{}'", other)),
+        };
+        Ok(Some(GenerateOptions {
+            source,
+            seed,
+            count,
+            constraints,
+            attempts,
+            dictionary,
+        }))
+    }
+}
+
+// Accept both "--option value" and "--option=value"
+fn split_flag(arg: &str) -> (&str, Option<String>) {
+    match arg.find('=') {
+        Some(i) if arg.starts_with("--") => (&arg[..i], Some(String::from(&arg[i + 1..]))),
+        _ => (arg, None),
+    }
+}
+
+// TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Options>, String> {
+        Options::parse(args.iter().map(|a| String::from(*a)))
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let options = parse(&["--grid", "a b; c d"]).unwrap().unwrap();
+        assert_eq!(
+            options.grid,
+            Some(GridSource::Inline(String::from("a b; c d")))
+        );
+        assert_eq!(options.dictionary, DictionaryOptions::default());
+        assert_eq!(options.dictionary.path, PathBuf::from("words_alpha.txt"));
+        assert_eq!(options.dictionary.kind, DictionaryKind::Trie);
+        assert_eq!(options.dictionary.alphabet, None);
+        assert!(!options.dictionary.strict);
+        assert_eq!(options.config, SolverConfig::wordament());
+        assert_eq!(options.scoring, Scoring::Wordament);
+        assert_eq!(options.format, Format::Text);
+        assert_eq!(options.sort, SortOrder::Found);
+        assert_eq!(options.group_by, None);
+        assert_eq!(options.render, None);
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options = parse(&[
+            "-f",
+            "board.txt",
+            "--dictionary=words.txt",
+            "--strict",
+            "--dictionary-kind",
+            "dawg",
+            "-a",
+            "german",
+            "-m",
+            "4",
+            "--max-length=8",
+            "--all-paths",
+            "--scoring",
+            "boggle",
+            "-o",
+            "words",
+            "--sort=score",
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            options.grid,
+            Some(GridSource::File(PathBuf::from("board.txt")))
+        );
+        assert_eq!(
+            options.dictionary,
+            DictionaryOptions {
+                path: PathBuf::from("words.txt"),
+                kind: DictionaryKind::Dawg,
+                alphabet: Some(Alphabet::german()),
+                strict: true,
+            }
+        );
+        assert_eq!(
+            options.config,
+            SolverConfig {
+                min_word_length: 4,
+                max_word_length: Some(8),
+                keep_other_paths: true,
+            }
+        );
+        assert_eq!(options.scoring, Scoring::Boggle);
+        assert_eq!(options.format, Format::Words);
+        assert_eq!(options.sort, SortOrder::Score);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse(&["--grid", "a", "--help"]), Ok(None));
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["--grid"]).is_err());
+        assert_eq!(parse(&["-i"]).unwrap().unwrap().grid, None);
+        assert!(parse(&["-i", "--grid", "a"]).is_err());
+        assert!(parse(&["--grid", "a", "-m", "three"]).is_err());
+        assert!(parse(&["--grid", "a", "-M", "-1"]).is_err());
+        assert!(parse(&["--grid", "a", "--scoring", "scrabble"]).is_err());
+        assert!(parse(&["--grid", "a", "--alphabet", "klingon"]).is_err());
+        assert!(parse(&["--grid", "a", "-k", "btree"]).is_err());
+        assert!(parse(&["--grid", "a", "--verbose"]).is_err());
+        assert!(parse(&["--grid", "a", "-o", "xml"]).is_err());
+        assert!(parse(&["--grid", "a", "--group-by", "score"]).is_err());
+        assert!(parse(&["--grid", "a", "--group-by", "start", "-o", "csv"]).is_err());
+        let grouped = parse(&["--grid", "a", "--group-by=length", "--sort", "start"]);
+        let grouped = grouped.unwrap().unwrap();
+        assert_eq!(grouped.group_by, Some(Grouping::Length));
+        assert_eq!(grouped.sort, SortOrder::StartCell);
+        let render = |word: &str| parse(&["--grid", "a", "-r", word]).unwrap().unwrap().render;
+        assert_eq!(render("Coats"), Some(Render::Word(String::from("coats"))));
+        // "all" is a word like any other
+        assert_eq!(render("all"), Some(Render::Word(String::from("all"))));
+        let render_all = parse(&["--grid", "a", "--render-all"]).unwrap().unwrap();
+        assert_eq!(render_all.render, Some(Render::All));
+        assert!(parse(&["--grid", "a", "--render-all", "-o", "words"]).is_err());
+        let svg = parse(&[
+            "--grid", "a", "-r", "cat", "-o", "words", "--svg", "cat.svg",
+        ]);
+        assert_eq!(svg.unwrap().unwrap().svg, Some(PathBuf::from("cat.svg")));
+        let json = parse(&["--grid", "a", "-o", "json"]);
+        #[cfg(feature = "serde")]
+        assert_eq!(json.unwrap().unwrap().format, Format::Json);
+        #[cfg(not(feature = "serde"))]
+        assert!(json.is_err());
+    }
+
+    #[test]
+    fn test_parse_compile() {
+        let compile = |args: &[&str]| CompileOptions::parse(args.iter().map(|a| String::from(*a)));
+        assert_eq!(
+            compile(&["words.txt", "--alphabet=french", "words.dawg"]),
+            Ok(Some(CompileOptions {
+                words: PathBuf::from("words.txt"),
+                output: PathBuf::from("words.dawg"),
+                alphabet: Alphabet::french(),
+                strict: false,
+            }))
+        );
+        assert_eq!(compile(&["words.txt", "-h"]), Ok(None));
+        assert!(compile(&["words.txt"]).is_err());
+        assert!(compile(&["a.txt", "b.txt", "c.txt"]).is_err());
+        assert!(compile(&["--grid", "a b", "words.txt", "words.dawg"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_generate() {
+        let generate =
+            |args: &[&str]| GenerateOptions::parse(args.iter().map(|a| String::from(*a)));
+        assert_eq!(
+            generate(&[]),
+            Ok(Some(GenerateOptions {
+                source: LetterSource::ClassicDice,
+                seed: None,
+                count: 1,
+                constraints: Constraints::default(),
+                attempts: 1000,
+                dictionary: DictionaryOptions::default(),
+            }))
+        );
+        assert_eq!(
+            generate(&["--dice=english", "--size", "6x3", "--seed", "42", "-n", "3"]),
+            Ok(Some(GenerateOptions {
+                source: LetterSource::EnglishFrequencies {
+                    width: 6,
+                    height: 3
+                },
+                seed: Some(42),
+                count: 3,
+                constraints: Constraints::default(),
+                attempts: 1000,
+                dictionary: DictionaryOptions::default(),
+            }))
+        );
+        let big = generate(&["--dice", "big", "--size=5x5"]).unwrap().unwrap();
+        assert_eq!(big.source, LetterSource::BigBoggleDice);
+        assert_eq!(generate(&["--seed", "1", "--help"]), Ok(None));
+        assert!(generate(&["--dice", "big", "--size", "4x4"]).is_err());
+        assert!(generate(&["--dice", "loaded"]).is_err());
+        assert!(generate(&["--dice=english", "--size", "0x4"]).is_err());
+        assert!(generate(&["--size", "four"]).is_err());
+        assert!(generate(&["--seed", "-1"]).is_err());
+        assert!(generate(&["board.txt"]).is_err());
+
+        let constrained = generate(&[
+            "--min-words=100",
+            "--min-longest",
+            "7",
+            "--target-score",
+            "250",
+            "--attempts",
+            "50",
+            "-d",
+            "words.dawg",
+        ]);
+        let constrained = constrained.unwrap().unwrap();
+        assert_eq!(
+            constrained.constraints,
+            Constraints {
+                min_words: Some(100),
+                min_longest_word: Some(7),
+                target_score: Some(250),
+            }
+        );
+        assert_eq!(constrained.attempts, 50);
+        assert_eq!(constrained.dictionary.path, PathBuf::from("words.dawg"));
+        let kind = generate(&["--min-words=10", "-k", "dawg", "--alphabet=french"]);
+        let kind = kind.unwrap().unwrap();
+        assert_eq!(kind.dictionary.kind, DictionaryKind::Dawg);
+        assert_eq!(kind.dictionary.alphabet, Some(Alphabet::french()));
+        assert!(generate(&["-k", "btree"]).is_err());
+        assert!(generate(&["--min-words", "lots"]).is_err());
+        assert!(generate(&["--attempts", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_read_board() {
+        let read = |text: &str| {
+            let mut out: Vec<u8> = Vec::new();
+            let board = read_board(&mut text.as_bytes(), &mut out).unwrap();
+            (board, String::from_utf8(out).unwrap())
+        };
+
+        let (board, prompts) = read("a b c d\ne f g h\n| i | j | qu:9 | re- |\nm n o -ing\n");
+        let grid = board.unwrap().unwrap();
+        assert_eq!(grid.get(2, 2).letters, "qu");
+        assert_eq!(grid.get(2, 2).value, 9);
+        assert_eq!(grid.get(3, 3).letters, "ing");
+        assert_eq!(prompts, "\nboard> (12 more)> (8 more)> (4 more)> ");
+
+        let (board, _) = read("abcdefghijklmnop\n");
+        let grid = board.unwrap().unwrap();
+        assert_eq!(grid.get(1, 0).letters, "e");
+        assert_eq!(grid.get(3, 3).letters, "p");
+
+        assert!(read("a b c d e f g h i j k l m n o p q\n")
+            .0
+            .unwrap()
+            .is_err());
+        assert!(read("a b c d e f g h i j k l m n o p:x\n")
+            .0
+            .unwrap()
+            .is_err());
+        assert!(read("quit\n").0.is_none());
+        // "q" is a tile, not a way to quit
+        let (board, _) = read("q\nu i c k b r o w n f o x j m p\n");
+        assert_eq!(board.unwrap().unwrap().get(0, 0).letters, "q");
+        assert!(read("a b c\n").0.is_none());
+    }
+
+    #[test]
+    fn test_interactive() {
+        let words: Vec<String> = ["cat", "cats", "taco", "dog"]
+            .iter()
+            .map(|w| String::from(*w))
+            .collect();
+        let dictionary = LoadedDictionary::Trie(GraphDictionary::new(&words).unwrap());
+        let options = parse(&["-i", "-o", "words", "--sort", "alpha"])
+            .unwrap()
+            .unwrap();
+        let input =
+            "catsoxxxxxxxxxxx\nx x x x x x x x\nx x x x x x x \u{e9}\ndogxxxxxxxxxxxxx\nquit\n";
+        let mut out: Vec<u8> = Vec::new();
+        interactive(
+            &mut input.as_bytes(),
+            &mut out,
+            &options,
+            &dictionary,
+            &Alphabet::english(),
+        )
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        // Each board is solved with the same dictionary, and a bad board doesn't stop the next one
+        let boards: Vec<&str> = out.split("board> ").skip(1).collect();
+        assert_eq!(boards.len(), 4);
+        assert_eq!(boards[0], "cat\ncats\ntaco\n\n");
+        assert!(boards[1].contains("error: can't solve this grid"));
+        assert_eq!(boards[2], "dog\n\n");
+        assert_eq!(boards[3], "");
+    }
 }
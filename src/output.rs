@@ -0,0 +1,166 @@
+use std::io::{self, Write};
+
+use crate::grid::Grid;
+use crate::FoundWord;
+#[cfg(feature = "serde")]
+use crate::Solution;
+
+/** The character separating the fields of each row in write_delimited.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Delimiter {
+    /// Comma-separated values. Fields containing commas or quotes are quoted.
+    Comma,
+    /// Tab-separated values. Fields never contain tabs, so nothing is quoted.
+    Tab,
+}
+
+/** Write a header row and then one row per word, with the columns word, length, score and path. The path lists each
+ * tile used as letters(row,col), separated by spaces. The total score can be found by adding up the score column.
+ */
+pub fn write_delimited<W: Write>(
+    out: &mut W,
+    grid: &Grid,
+    words: &[&FoundWord],
+    delimiter: Delimiter,
+) -> io::Result<()> {
+    let separator = match delimiter {
+        Delimiter::Comma => ",",
+        Delimiter::Tab => "\t",
+    };
+    writeln!(
+        out,
+        "{}",
+        ["word", "length", "score", "path"].join(separator)
+    )?;
+    for found in words {
+        let fields = [
+            found.word.clone(),
+            found.word.chars().count().to_string(),
+            found.score.to_string(),
+            format_path(grid, found),
+        ];
+        let fields: Vec<String> = match delimiter {
+            Delimiter::Comma => fields.iter().map(|f| quote_csv(f)).collect(),
+            Delimiter::Tab => fields.to_vec(),
+        };
+        writeln!(out, "{}", fields.join(separator))?;
+    }
+    Ok(())
+}
+
+/** Write the grid, the total score and the given words (in that order) as one JSON object:
+ * `{"grid": {...}, "score": N, "words": [{"word": ..., "path": [...], "score": N, "other_paths": [...]}, ...]}`
+ * (other_paths is left out unless the solution kept them).
+ */
+#[cfg(feature = "serde")]
+pub fn write_json<W: Write>(
+    out: &mut W,
+    grid: &Grid,
+    solution: &Solution,
+    words: &[&FoundWord],
+) -> io::Result<()> {
+    #[derive(serde::Serialize)]
+    struct Output<'a> {
+        grid: &'a Grid,
+        score: u32,
+        words: &'a [&'a FoundWord],
+    }
+    serde_json::to_writer_pretty(
+        &mut *out,
+        &Output {
+            grid,
+            score: solution.score(),
+            words,
+        },
+    )?;
+    writeln!(out)
+}
+
+/** List each tile in a word's path as letters(row,col), separated by spaces.
+ */
+pub fn format_path(grid: &Grid, found: &FoundWord) -> String {
+    found
+        .path
+        .iter()
+        .map(|step| {
+            let letters = grid
+                .get(step.row, step.col)
+                .choice(step.choice)
+                .unwrap_or("?");
+            format!("{}({},{})", letters, step.row, step.col)
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// Quote a CSV field if it needs it, doubling any quotes inside it
+fn quote_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        String::from(field)
+    }
+}
+
+// TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PathStep;
+
+    fn example() -> (Grid, FoundWord) {
+        let grid: Grid = "qu:8 i:2\nt:2 e/a:1".parse().unwrap();
+        let found = FoundWord::new(
+            String::from("quite"),
+            vec![
+                PathStep::new(0, 0, 0),
+                PathStep::new(0, 1, 0),
+                PathStep::new(1, 0, 0),
+                PathStep::new(1, 1, 0),
+            ],
+            19,
+        );
+        (grid, found)
+    }
+
+    #[test]
+    fn test_delimited() {
+        let (grid, found) = example();
+        let mut out: Vec<u8> = Vec::new();
+        write_delimited(&mut out, &grid, &[&found], Delimiter::Comma).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "word,length,score,path\nquite,5,19,\"qu(0,0) i(0,1) t(1,0) e(1,1)\"\n"
+        );
+        let mut out: Vec<u8> = Vec::new();
+        write_delimited(&mut out, &grid, &[&found], Delimiter::Tab).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "word\tlength\tscore\tpath\nquite\t5\t19\tqu(0,0) i(0,1) t(1,0) e(1,1)\n"
+        );
+        assert_eq!(quote_csv("a,b"), "\"a,b\"");
+        assert_eq!(quote_csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json() {
+        let (grid, found) = example();
+        let solution = Solution::new(vec![found.clone()]);
+        let mut out: Vec<u8> = Vec::new();
+        write_json(&mut out, &grid, &solution, &[&found]).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["score"], 19);
+        assert_eq!(json["grid"]["width"], 2);
+        assert_eq!(json["grid"]["rows"][1][1]["alternatives"][0], "a");
+        assert_eq!(json["grid"]["rows"][0][0]["placement"], "anywhere");
+        assert_eq!(json["words"][0]["word"], "quite");
+        assert_eq!(json["words"][0]["path"][2]["row"], 1);
+        assert_eq!(json["words"][0]["path"].as_array().unwrap().len(), 4);
+        // A Solution serializes its words and total score on its own too
+        let json = serde_json::to_value(&solution).unwrap();
+        assert_eq!(json["score"], 19);
+        assert_eq!(json["words"][0]["score"], 19);
+    }
+}
@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+
+use crate::grid::Grid;
+use crate::{FoundWord, PathStep};
+
+/** Draw the grid as text, the same way the Debug output for a Grid lays it out, with the position of each tile in the
+ * path written next to it. Tiles that aren't part of the path are left unnumbered, and either/or tiles in the path
+ * only show the letters that were played. For the path c(0,0) a(0,1) t(1,1):
+ * ```text
+ * | 1 c | 2 a |
+ * |   o | 3 t |
+ * ```
+ */
+pub fn render_path(grid: &Grid, path: &[PathStep]) -> String {
+    let steps: HashMap<(usize, usize), (usize, usize)> = path
+        .iter()
+        .enumerate()
+        .map(|(i, step)| ((step.row, step.col), (i + 1, step.choice)))
+        .collect();
+    let labels: Vec<Vec<(String, String)>> = (0..grid.height)
+        .map(|row| {
+            (0..grid.width)
+                .map(|col| match steps.get(&(row, col)) {
+                    Some(&(number, choice)) => {
+                        (number.to_string(), grid.get(row, col).label(Some(choice)))
+                    }
+                    None => (String::new(), grid.get(row, col).label(None)),
+                })
+                .collect()
+        })
+        .collect();
+
+    let number_width = path.len().to_string().len();
+    let letters_width = labels
+        .iter()
+        .flatten()
+        .map(|(_, letters)| letters.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut rendered = String::new();
+    for row in labels {
+        rendered.push('|');
+        for (number, letters) in row {
+            rendered.push_str(&format!(
+                " {:>nw$} {:<lw$} |",
+                number,
+                letters,
+                nw = number_width,
+                lw = letters_width
+            ));
+        }
+        rendered.push('\n');
+    }
+    rendered
+}
+
+/** Draw a found word's best path on the grid (see render_path), under a line giving the word and its score.
+ */
+pub fn render_word(grid: &Grid, found: &FoundWord) -> String {
+    format!(
+        "{} ({} points)\n{}",
+        found.word,
+        found.score,
+        render_path(grid, &found.path)
+    )
+}
+
+/** The colors paths are drawn in by render_svg, in order. After the last one, the colors repeat.
+ */
+pub const PATH_COLORS: [&str; 6] = [
+    "#1f77b4", "#d62728", "#2ca02c", "#9467bd", "#8c564b", "#e377c2",
+];
+
+// Sizes in render_svg, in pixels
+const TILE_SIZE: usize = 80;
+const TILE_GAP: usize = 8;
+const MARGIN: usize = 16;
+const LEGEND_LINE: usize = 24;
+
+/** Draw the grid as a standalone SVG image, with the best path of each word drawn over it as a colored line that
+ * starts with a dot on the first tile. Every tile shows its letters and, in the corner, its value. A legend under the
+ * grid gives each word and its score in the color of its path. When several paths cross the same tiles, each is
+ * nudged slightly so they can be told apart.
+ */
+pub fn render_svg(grid: &Grid, words: &[&FoundWord]) -> String {
+    let pitch = TILE_SIZE + TILE_GAP;
+    let grid_width = (grid.width * pitch).saturating_sub(TILE_GAP);
+    let grid_height = (grid.height * pitch).saturating_sub(TILE_GAP);
+    let width = grid_width + 2 * MARGIN;
+    let height = grid_height + 2 * MARGIN + words.len() * LEGEND_LINE;
+
+    let mut svg = String::new();
+    svg.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" \
+         font-family=\"Helvetica, Arial, sans-serif\">\n",
+        w = width,
+        h = height
+    ));
+    svg.push_str(&format!(
+        "  <rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>\n",
+        width, height
+    ));
+
+    for row in 0..grid.height {
+        for col in 0..grid.width {
+            let cell = grid.get(row, col);
+            let (x, y) = (MARGIN + col * pitch, MARGIN + row * pitch);
+            svg.push_str(&format!(
+                "  <rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" rx=\"6\" fill=\"#f5a623\"/>\n",
+                x,
+                y,
+                s = TILE_SIZE
+            ));
+            let letters = cell.label(None);
+            let font_size = if letters.chars().count() > 2 { 24 } else { 36 };
+            svg.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\" font-size=\"{}\" font-weight=\"bold\" text-anchor=\"middle\" \
+                 dominant-baseline=\"central\" fill=\"#ffffff\">{}</text>\n",
+                x + TILE_SIZE / 2,
+                y + TILE_SIZE / 2,
+                font_size,
+                escape_xml(&letters.to_uppercase())
+            ));
+            svg.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\" font-size=\"14\" text-anchor=\"end\" fill=\"#ffffff\">{}</text>\n",
+                x + TILE_SIZE - 6,
+                y + 18,
+                cell.value
+            ));
+        }
+    }
+
+    for (i, found) in words.iter().enumerate() {
+        let color = PATH_COLORS[i % PATH_COLORS.len()];
+        // Spread overlapping paths out around the middle of each tile
+        let nudge = (i % 5) as f64 * 4.0 - 8.0;
+        let center = |step: &PathStep| {
+            (
+                (MARGIN + step.col * pitch + TILE_SIZE / 2) as f64 + nudge,
+                (MARGIN + step.row * pitch + TILE_SIZE / 2) as f64 + nudge,
+            )
+        };
+        let points: Vec<String> = found
+            .path
+            .iter()
+            .map(|step| {
+                let (x, y) = center(step);
+                format!("{},{}", x, y)
+            })
+            .collect();
+        svg.push_str(&format!(
+            "  <polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"6\" \
+             stroke-linecap=\"round\" stroke-linejoin=\"round\" stroke-opacity=\"0.8\"/>\n",
+            points.join(" "),
+            color
+        ));
+        if let Some(first) = found.path.first() {
+            let (x, y) = center(first);
+            svg.push_str(&format!(
+                "  <circle cx=\"{}\" cy=\"{}\" r=\"9\" fill=\"{}\"/>\n",
+                x, y, color
+            ));
+        }
+        svg.push_str(&format!(
+            "  <text x=\"{}\" y=\"{}\" font-size=\"16\" fill=\"{}\">{} ({} points)</text>\n",
+            MARGIN,
+            MARGIN + grid_height + (i + 1) * LEGEND_LINE,
+            color,
+            escape_xml(&found.word),
+            found.score
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// Escape the characters that have special meanings in XML text and attributes
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_path() {
+        let grid: Grid = "c a\no t".parse().unwrap();
+        let path = [
+            PathStep::new(0, 0, 0),
+            PathStep::new(0, 1, 0),
+            PathStep::new(1, 1, 0),
+        ];
+        assert_eq!(render_path(&grid, &path), "| 1 c | 2 a |\n|   o | 3 t |\n");
+        assert_eq!(render_path(&grid, &[]), "|   c |   a |\n|   o |   t |\n");
+    }
+
+    #[test]
+    fn test_render_special_tiles() {
+        let grid: Grid = "re- d a/e\n-ing o x\nb c f\ng h i\nj k l".parse().unwrap();
+        let found = FoundWord::new(
+            String::from("redo"),
+            vec![
+                PathStep::new(0, 0, 0),
+                PathStep::new(0, 1, 0),
+                PathStep::new(1, 1, 0),
+            ],
+            11,
+        );
+        let rendered = render_word(&grid, &found);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "redo (11 points)");
+        assert_eq!(lines[1], "| 1 re-  | 2 d    |   a/e  |");
+        assert_eq!(lines[2], "|   -ing | 3 o    |   x    |");
+
+        // Only the letters that were played are shown on an either/or tile
+        let rendered = render_path(&grid, &[PathStep::new(0, 2, 1)]);
+        assert!(rendered.starts_with("|   re-  |   d    | 1 e    |\n"));
+    }
+
+    #[test]
+    fn test_render_long_path() {
+        let grid: Grid = "a b c d\ne f g h\ni j k l".parse().unwrap();
+        let path: Vec<PathStep> = (0..4)
+            .map(|col| PathStep::new(0, col, 0))
+            .chain((0..4).rev().map(|col| PathStep::new(1, col, 0)))
+            .chain((0..4).map(|col| PathStep::new(2, col, 0)))
+            .collect();
+        let rendered = render_path(&grid, &path);
+        // Step numbers are padded to the same width
+        assert_eq!(
+            rendered.lines().nth(1).unwrap(),
+            "|  8 e |  7 f |  6 g |  5 h |"
+        );
+        assert_eq!(
+            rendered.lines().nth(2).unwrap(),
+            "|  9 i | 10 j | 11 k | 12 l |"
+        );
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let grid: Grid = "c:3 a:2\nqu:9 -ing:7".parse().unwrap();
+        let cat = FoundWord::new(
+            String::from("cat"),
+            vec![PathStep::new(0, 0, 0), PathStep::new(0, 1, 0)],
+            5,
+        );
+        let svg = render_svg(&grid, &[&cat, &cat]);
+        assert!(svg
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"200\" height=\"248\""));
+        assert!(svg.ends_with("</svg>\n"));
+        // Tiles show their letters (uppercased) and values
+        assert!(svg.contains(">QU</text>"));
+        assert!(svg.contains(">-ING</text>"));
+        assert!(svg.contains(">9</text>"));
+        // Each path is a polyline through the middle of its tiles, nudged so they don't hide each other
+        assert!(svg.contains("<polyline points=\"48,48 136,48\" fill=\"none\" stroke=\"#1f77b4\""));
+        assert!(svg.contains("<polyline points=\"52,52 140,52\" fill=\"none\" stroke=\"#d62728\""));
+        assert_eq!(svg.matches("<circle").count(), 2);
+        assert!(svg.contains(">cat (5 points)</text>"));
+
+        assert_eq!(escape_xml("<a & 'b'>"), "&lt;a &amp; &apos;b&apos;&gt;");
+    }
+}
@@ -0,0 +1,117 @@
+use std::convert::TryFrom;
+
+use crate::alphabet;
+
+/** Scoring models for found words. Different games reward words differently: Wordament adds up the value of each
+ * tile and then applies a bonus for longer words, while Boggle ignores tile values entirely and only looks at length.
+ */
+pub trait Scorer: Send + Sync {
+    /**
+     * Score a word, given the value of every tile used to make it (in the order they were used).
+     */
+    fn score(&self, word: &str, tile_values: &[u32]) -> u32;
+}
+
+/** Wordament scoring: the sum of the tile values, multiplied by a bonus based on the length of the word.
+ * - 1-4 letters: x1
+ * - 5-6 letters: x1.5
+ * - 7 letters: x2
+ * - 8+ letters: x2.5
+ *
+ * Fractional scores are rounded down.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WordamentScorer;
+impl WordamentScorer {
+    // The length bonus, in percent
+    fn multiplier(length: usize) -> u32 {
+        match length {
+            0..=4 => 100,
+            5..=6 => 150,
+            7 => 200,
+            _ => 250,
+        }
+    }
+}
+impl Scorer for WordamentScorer {
+    fn score(&self, word: &str, tile_values: &[u32]) -> u32 {
+        // Tile values can be anything, so add them up in u64 and cap the score rather than overflowing
+        let sum: u64 = tile_values.iter().map(|&v| u64::from(v)).sum();
+        let score = sum * u64::from(WordamentScorer::multiplier(word.chars().count())) / 100;
+        u32::try_from(score).unwrap_or(u32::MAX)
+    }
+}
+
+/** Classic Boggle scoring, which only depends on the length of the word:
+ * - 3-4 letters: 1 point
+ * - 5 letters: 2 points
+ * - 6 letters: 3 points
+ * - 7 letters: 5 points
+ * - 8+ letters: 11 points
+ *
+ * Words shorter than 3 letters aren't allowed in Boggle, so they score nothing.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BoggleScorer;
+impl Scorer for BoggleScorer {
+    fn score(&self, word: &str, _tile_values: &[u32]) -> u32 {
+        match word.chars().count() {
+            0..=2 => 0,
+            3..=4 => 1,
+            5 => 2,
+            6 => 3,
+            7 => 5,
+            _ => 11,
+        }
+    }
+}
+
+/** The value Wordament usually gives a tile, based on how hard its letter is to use. Accented letters are worth the
+ * same as the letter they're based on, and anything else that isn't a letter from a to z is worth nothing.
+ */
+pub fn wordament_letter_value(letter: char) -> u32 {
+    match alphabet::base_letter(letter).to_ascii_lowercase() {
+        'e' => 1,
+        'a' | 'i' | 'n' | 'o' | 'r' | 's' | 't' => 2,
+        'c' | 'd' | 'l' => 3,
+        'g' | 'h' | 'm' | 'p' | 'u' => 4,
+        'b' | 'f' | 'y' => 5,
+        'k' | 'v' | 'w' => 6,
+        'q' | 'z' => 8,
+        'x' => 9,
+        'j' => 10,
+        _ => 0,
+    }
+}
+
+// TESTS
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_wordament() {
+        let scorer = WordamentScorer;
+        assert_eq!(scorer.score("cat", &[3, 1, 2]), 6);
+        assert_eq!(scorer.score("cats", &[3, 1, 2, 2]), 8);
+        assert_eq!(scorer.score("coats", &[3, 1, 1, 2, 2]), 13);
+        assert_eq!(scorer.score("coasts", &[3, 1, 1, 2, 2, 2]), 16);
+        assert_eq!(scorer.score("coaster", &[3, 1, 1, 2, 2, 1, 2]), 24);
+        assert_eq!(scorer.score("coasters", &[3, 1, 1, 2, 2, 1, 2, 2]), 35);
+        assert_eq!(scorer.score("at", &[u32::MAX, 1]), u32::MAX);
+        assert_eq!(
+            scorer.score("coats", &[u32::MAX / 3 * 2, 1, 1, 2, 2]),
+            u32::MAX
+        );
+    }
+
+    #[test]
+    fn test_boggle() {
+        let scorer = BoggleScorer;
+        assert_eq!(scorer.score("at", &[1, 1]), 0);
+        assert_eq!(scorer.score("cat", &[3, 1, 2]), 1);
+        assert_eq!(scorer.score("coats", &[10, 10, 10, 10, 10]), 2);
+        assert_eq!(scorer.score("coasts", &[]), 3);
+        assert_eq!(scorer.score("coaster", &[]), 5);
+        assert_eq!(scorer.score("coasters", &[]), 11);
+    }
+}